@@ -0,0 +1,62 @@
+//! 数MBのJSONCをparseし、所要時間とallocationの回数・量を出力する
+//! `cargo bench --bench parse`
+use jsonc_wasm::lexer::Lexer;
+use jsonc_wasm::parser::Parser;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn generate_input(items: usize) -> String {
+    let mut input = String::from("{\n  // generated\n  \"items\": [\n");
+    for i in 0..items {
+        input.push_str(&format!(
+            "    {{\"id\": {}, \"name\": \"item-{}\", \"enabled\": true, \"tags\": [\"a\", \"b\"], \"parent\": null}}, /* {} */\n",
+            i, i, i
+        ));
+    }
+    input.push_str("  ],\n}\n");
+    input
+}
+
+fn main() {
+    let input = generate_input(40_000);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let start = Instant::now();
+
+    let tokens = Lexer::new(&input).tokenize().unwrap();
+    let node = Parser::new(tokens).parse().unwrap();
+
+    let elapsed = start.elapsed();
+    println!("input: {} bytes", input.len());
+    println!("time: {:?}", elapsed);
+    println!(
+        "allocations: {}",
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations
+    );
+    println!(
+        "allocated: {} bytes",
+        ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes
+    );
+    drop(node);
+}
@@ -0,0 +1,118 @@
+use crate::lexer::Lexer;
+use crate::token::{Location, Spanned, Token};
+use std::collections::BTreeMap;
+
+/// `//`と`/* */`を除いたコメントの本文
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Comment {
+    Line(String),
+    Block(String),
+}
+
+impl Comment {
+    /// JSONCとして出力する
+    /// 行コメントは改行まで続くため、後ろに値を続ける場合は改行が必要
+    pub fn to_jsonc_string(&self) -> String {
+        match self {
+            Comment::Line(text) => format!("//{}", text),
+            Comment::Block(text) => format!("/*{}*/", text),
+        }
+    }
+}
+
+/// コメントの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentKind {
+    Line,
+    Block,
+}
+
+/// 入力に含まれる全てのコメントを、出現順に位置とあわせて返す
+/// parseに失敗する入力でも、lexerが読み取れた範囲のコメントは返す
+/// ```
+/// use jsonc_wasm::comment::{extract_comments, CommentKind};
+/// use jsonc_wasm::Location;
+/// let comments = extract_comments("[1 2 /* c */");
+/// assert_eq!(vec![(CommentKind::Block, " c ".to_string(), Location(5, 11))], comments);
+/// ```
+pub fn extract_comments(input: &str) -> Vec<(CommentKind, String, Location)> {
+    Lexer::new(input)
+        .filter_map(|token| match token {
+            Ok(Spanned {
+                value: Token::CommentLine(text),
+                location,
+                ..
+            }) => Some((CommentKind::Line, text, location)),
+            Ok(Spanned {
+                value: Token::CommentBlock(text),
+                location,
+                ..
+            }) => Some((CommentKind::Block, text, location)),
+            // 不正な文字は読み飛ばし、続きのコメントを探す
+            _ => None,
+        })
+        .collect()
+}
+
+/// nodeのJSON Pointerをkeyにしたコメントの対応表
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Comments {
+    leading: BTreeMap<String, Vec<Comment>>, // nodeの直前(objectのmemberはkeyの直前)
+    trailing: BTreeMap<String, Vec<Comment>>, // object/arrayの閉じ括弧の直前
+    end: Vec<Comment>,                       // rootの値の後ろ
+}
+
+impl Comments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leading.is_empty() && self.trailing.is_empty() && self.end.is_empty()
+    }
+
+    pub fn leading(&self, pointer: &str) -> &[Comment] {
+        self.leading.get(pointer).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn trailing(&self, pointer: &str) -> &[Comment] {
+        self.trailing.get(pointer).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn end(&self) -> &[Comment] {
+        &self.end
+    }
+
+    pub(crate) fn push_leading(&mut self, pointer: String, comments: Vec<Comment>) {
+        if !comments.is_empty() {
+            self.leading.entry(pointer).or_default().extend(comments);
+        }
+    }
+
+    pub(crate) fn push_trailing(&mut self, pointer: String, comments: Vec<Comment>) {
+        if !comments.is_empty() {
+            self.trailing.entry(pointer).or_default().extend(comments);
+        }
+    }
+
+    pub(crate) fn push_end(&mut self, comments: Vec<Comment>) {
+        self.end.extend(comments);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_comments_should_skip_invalid_chars() {
+        let input = "// head\n{ @ \"a\": /* x */ 1 ";
+        assert_eq!(
+            vec![
+                (CommentKind::Line, " head".to_string(), Location(0, 6)),
+                (CommentKind::Block, " x ".to_string(), Location(17, 23)),
+            ],
+            extract_comments(input)
+        );
+    }
+}
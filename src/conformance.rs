@@ -0,0 +1,107 @@
+//! JSONTestSuite(https://github.com/nst/JSONTestSuite)を使ったRFC 8259準拠テスト
+//! `y_`は受理、`n_`は拒否されなければならず、`i_`は実装依存
+//! テストデータは`tests/data/json_test_suite`に置いている
+use crate::lexer::Lexer;
+use crate::options::ParseOptions;
+use crate::parser::{ParseError, Parser};
+use std::fs;
+
+const SUITE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/json_test_suite");
+
+/// 設計上の判断で`y_`/`n_`の分類と結果を逆にしているもの
+/// 理由と共に記載し、不具合はここに加えず修正する
+/// 現在、strict modeはすべての分類に一致する
+const KNOWN_DEVIATIONS: &[(&str, &str)] = &[];
+
+/// `i_`の結果(受理するか)とその理由
+const IMPLEMENTATION_DEFINED: &[(&str, bool, &str)] = &[
+    // 数値は文字列のまま保持するため桁数の制限はない
+    ("i_number_huge_exp.json", true, "numbers are kept as text"),
+    (
+        "i_number_too_big_neg_int.json",
+        true,
+        "numbers are kept as text",
+    ),
+    (
+        "i_number_very_big_negative_int.json",
+        true,
+        "numbers are kept as text",
+    ),
+    // 対にならないsurrogateはInvalidUnicodeEscapeとする
+    (
+        "i_string_1st_surrogate_but_2nd_missing.json",
+        false,
+        "lone surrogate",
+    ),
+    (
+        "i_string_incomplete_surrogate_pair.json",
+        false,
+        "lone surrogate",
+    ),
+    // 入力は`&str`のため、UTF-8以外とBOMは受け付けない
+    ("i_string_UTF-16LE_with_BOM.json", false, "not UTF-8"),
+    ("i_string_invalid_utf-8.json", false, "not UTF-8"),
+    ("i_structure_UTF-8_BOM_empty_object.json", false, "BOM"),
+    // DEFAULT_MAX_DEPTHを超える
+    ("i_structure_500_nested_arrays.json", false, "max depth"),
+];
+
+/// strict modeの設定でparseできるか
+fn accepts(bytes: &[u8]) -> bool {
+    let Ok(input) = std::str::from_utf8(bytes) else {
+        return false;
+    };
+    let options = ParseOptions::strict();
+    Lexer::with_options(input, options)
+        .tokenize()
+        .map_err(ParseError::from)
+        .and_then(|tokens| Parser::with_options(tokens, options).parse())
+        .is_ok()
+}
+
+fn expected(name: &str) -> bool {
+    let expected = if name.starts_with("y_") {
+        true
+    } else if name.starts_with("n_") {
+        false
+    } else {
+        IMPLEMENTATION_DEFINED
+            .iter()
+            .find(|(file, _, _)| *file == name)
+            .map(|(_, accepted, _)| *accepted)
+            .unwrap_or_else(|| panic!("`{}`の結果が記載されていません。", name))
+    };
+    let deviated = KNOWN_DEVIATIONS.iter().any(|(file, _)| *file == name);
+    expected != deviated
+}
+
+#[test]
+fn json_test_suite_should_match_classification() {
+    let mut names = fs::read_dir(SUITE_DIR)
+        .expect("テストデータが読み込めません。")
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+
+    let mismatches = names
+        .iter()
+        .filter(|name| {
+            let bytes = fs::read(format!("{}/{}", SUITE_DIR, name)).unwrap();
+            accepts(&bytes) != expected(name)
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        mismatches.is_empty(),
+        "分類と一致しません: {:?}",
+        mismatches
+    );
+
+    // 記載された差異はすべてテストデータに存在する
+    for (file, _) in KNOWN_DEVIATIONS {
+        assert!(
+            names.iter().any(|name| name == file),
+            "`{}`がありません。",
+            file
+        );
+    }
+}
@@ -0,0 +1,57 @@
+/// BOMから判定した入力のencoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Unknown, // BOMなし
+}
+
+impl Encoding {
+    /// BOMのbyte数
+    pub fn bom_len(&self) -> usize {
+        match self {
+            Encoding::Utf8 => 3,
+            Encoding::Utf16Le | Encoding::Utf16Be => 2,
+            Encoding::Unknown => 0,
+        }
+    }
+}
+
+/// 先頭のBOMからencodingを判定する
+/// lexerは`&str`を扱うため、呼び出し側でdecodeする前の前処理として使う
+pub fn detect_encoding(bytes: &[u8]) -> Encoding {
+    match bytes {
+        [0xEF, 0xBB, 0xBF, ..] => Encoding::Utf8,
+        [0xFF, 0xFE, ..] => Encoding::Utf16Le,
+        [0xFE, 0xFF, ..] => Encoding::Utf16Be,
+        _ => Encoding::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_encoding_from_bom() {
+        assert_eq!(Encoding::Utf8, detect_encoding(b"\xEF\xBB\xBF{}"));
+        assert_eq!(Encoding::Utf16Le, detect_encoding(b"\xFF\xFE{\x00}\x00"));
+        assert_eq!(Encoding::Utf16Be, detect_encoding(b"\xFE\xFF\x00{\x00}"));
+    }
+
+    #[test]
+    fn detect_encoding_without_bom() {
+        assert_eq!(Encoding::Unknown, detect_encoding(b"{}"));
+        assert_eq!(Encoding::Unknown, detect_encoding(b"\xEF\xBB"));
+        assert_eq!(Encoding::Unknown, detect_encoding(b""));
+    }
+
+    #[test]
+    fn bom_len_should_match_encoding() {
+        let bytes = b"\xEF\xBB\xBF{}";
+        let encoding = detect_encoding(bytes);
+        assert_eq!(b"{}", &bytes[encoding.bom_len()..]);
+        assert_eq!(0, Encoding::Unknown.bom_len());
+    }
+}
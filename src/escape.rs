@@ -0,0 +1,145 @@
+//! `Node::StringValue`とobjectのkeyは、JSONのescapeを残した状態で保持している
+//! 実際の文字列と相互に変換するためのhelper
+
+/// JSONのescapeを解除する
+/// 不正なescapeはlexerで弾いているため、そのまま残す
+pub fn unescape_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('"') => result.push('"'),
+            Some('\\') => result.push('\\'),
+            Some('/') => result.push('/'),
+            Some('b') => result.push('\u{08}'),
+            Some('f') => result.push('\u{0C}'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some('u') => result.push(take_unicode_escape(&mut chars).unwrap_or('\u{FFFD}')),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// JSONの文字列として出力できるようescapeする
+pub fn escape_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\u{08}' => result.push_str("\\b"),
+            '\u{0C}' => result.push_str("\\f"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// `\uXXXX`のescape(surrogate pairを含む)だけを実際の文字に置き換える
+/// 制御文字や`"`、`\`、対になっていないsurrogateなど、escapeが必要なものはそのまま残す
+pub fn decode_unicode_escapes(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'u') {
+            let mut lookahead = chars.clone();
+            lookahead.next();
+            match take_unicode_escape(&mut lookahead) {
+                Some(decoded) if decoded >= '\u{20}' && decoded != '"' && decoded != '\\' => {
+                    result.push(decoded);
+                    chars = lookahead;
+                    continue;
+                }
+                _ => (),
+            }
+        }
+        result.push(c);
+        if let Some(escaped) = chars.next() {
+            result.push(escaped);
+        }
+    }
+    result
+}
+
+/// `\u`の後ろの16進数4桁を読み取る、high surrogateの場合は続くlow surrogateまで読み取る
+/// 対になっていないsurrogateは`None`
+fn take_unicode_escape(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<char> {
+    let code = match take_hex4(chars)? {
+        high @ 0xD800..=0xDBFF => {
+            let mut lookahead = chars.clone();
+            match (
+                lookahead.next(),
+                lookahead.next(),
+                take_hex4(&mut lookahead),
+            ) {
+                (Some('\\'), Some('u'), Some(low @ 0xDC00..=0xDFFF)) => {
+                    *chars = lookahead;
+                    0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                }
+                _ => return None,
+            }
+        }
+        code => code,
+    };
+    char::from_u32(code)
+}
+
+fn take_hex4(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<u32> {
+    let hex: String = (0..4).filter_map(|_| chars.next()).collect();
+    if hex.len() == 4 {
+        u32::from_str_radix(&hex, 16).ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_unicode_escapes_should_keep_required_escapes() {
+        assert_eq!("あ😀", decode_unicode_escapes(r"\u3042\uD83D\uDE00"));
+        assert_eq!(
+            r#"\n\"\u0022\u005C\u001f\\u3042\uD83D"#,
+            decode_unicode_escapes(r#"\n\"\u0022\u005C\u001f\\u3042\uD83D"#)
+        );
+    }
+
+    #[test]
+    fn unescape_string_should_decode_escapes() {
+        assert_eq!(
+            "test\"/\\\u{08}\n\u{0C}\r\t",
+            unescape_string(r#"test\"\/\\\b\n\f\r\t"#)
+        );
+        assert_eq!("あいうabc", unescape_string(r#"\u3042\u3044\u3046abc"#));
+        assert_eq!("😀👍", unescape_string(r#"\ud83d\ude00\ud83d\udc4d"#));
+    }
+
+    #[test]
+    fn escape_string_should_encode_special_chars() {
+        assert_eq!(r#"a\"b\\c/d\n"#, escape_string("a\"b\\c/d\n"));
+        assert_eq!(r#"\u0001あ"#, escape_string("\u{01}あ"));
+        let raw = "quote\" slash/ tab\t 😀";
+        assert_eq!(raw, unescape_string(&escape_string(raw)));
+    }
+}
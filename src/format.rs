@@ -0,0 +1,227 @@
+use crate::minify::{ends_value, is_trivia, starts_value};
+use crate::token::Token;
+
+/// `format`の設定
+/// ```
+/// # use jsonc_wasm::format::FormatOptions;
+/// let options = FormatOptions::new().indent(4).trailing_commas(true);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    indent: usize,
+    trailing_commas: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent: 2,
+            trailing_commas: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 1段あたりの空白の数
+    pub fn indent(mut self, width: usize) -> Self {
+        self.indent = width;
+        self
+    }
+
+    /// object/arrayの最後の要素の後ろに常にcommaを置く
+    /// `false`の場合は入力にあっても取り除く
+    pub fn trailing_commas(mut self, enabled: bool) -> Self {
+        self.trailing_commas = enabled;
+        self
+    }
+}
+
+/// 空白を除いたtokenと、直前に改行があったか
+type Item = (Token, bool);
+
+/// tokenを整形して書き出す
+/// 入力はparserで検証済みであること
+pub(crate) fn format_tokens(
+    tokens: impl IntoIterator<Item = Token>,
+    options: FormatOptions,
+) -> String {
+    let mut items: Vec<Item> = vec![];
+    let mut newline = false;
+    for token in tokens {
+        match token {
+            Token::BreakLine => newline = true,
+            token if is_trivia(&token) && !is_comment(&token) => (),
+            token => items.push((token, std::mem::take(&mut newline))),
+        }
+    }
+    let mut writer = Writer {
+        options,
+        output: String::new(),
+        level: 0,
+        pending_break: false,
+        pending_space: false,
+        after_open: false,
+        after_value: false,
+        value_end: 0,
+        after_block_comment: false,
+    };
+    for (index, (token, newline)) in items.iter().enumerate() {
+        writer.write_token(token, *newline, next_grammar(&items[index + 1..]));
+    }
+    if !writer.output.is_empty() {
+        writer.output.push('\n');
+    }
+    writer.output
+}
+
+struct Writer {
+    options: FormatOptions,
+    output: String,
+    level: usize,
+    pending_break: bool, // 次のtokenの前で改行する
+    pending_space: bool, // 次のtokenの前に空白を入れる
+    after_open: bool,    // 直前が開き括弧
+    after_value: bool,   // 直前が値の終わり
+    value_end: usize,    // 最後に書いた値の終わりの位置
+    after_block_comment: bool,
+}
+
+impl Writer {
+    fn write_token(&mut self, token: &Token, newline: bool, next: Option<&Token>) {
+        // ブロックコメントの後ろは、入力で改行していた場合のみ改行する
+        if std::mem::take(&mut self.after_block_comment) && newline {
+            self.pending_break = true;
+        }
+        // commaが省略された値の間にはcommaを補う
+        // 間にコメントがあっても、commaは値の直後に置く
+        if self.after_value && starts_value(token) {
+            self.output.insert(self.value_end, ',');
+            self.pending_break = true;
+        }
+        match token {
+            Token::OpenBrace | Token::OpenBracket => {
+                self.begin();
+                self.output
+                    .push(if *token == Token::OpenBrace { '{' } else { '[' });
+                self.level += 1;
+                self.pending_break = true;
+                self.after_open = true;
+            }
+            Token::CloseBrace | Token::CloseBracket => {
+                self.level -= 1;
+                // 空のobject/arrayは改行しない
+                if !self.after_open {
+                    self.pending_break = true;
+                    self.begin();
+                }
+                self.pending_break = false;
+                self.output.push(if *token == Token::CloseBrace {
+                    '}'
+                } else {
+                    ']'
+                });
+                self.after_open = false;
+            }
+            Token::Comma => {
+                if !is_closing(next) || self.options.trailing_commas {
+                    self.output.push(',');
+                }
+                self.pending_break = true;
+            }
+            Token::Colon => {
+                self.output.push(':');
+                self.pending_space = true;
+            }
+            Token::CommentLine(text) => {
+                self.begin_comment(newline, true);
+                self.output.push_str("//");
+                self.output.push_str(text);
+                self.pending_break = true;
+            }
+            Token::CommentBlock(text) => {
+                self.begin_comment(newline, false);
+                self.output.push_str("/*");
+                self.output.push_str(text);
+                self.output.push_str("*/");
+                self.pending_space = true;
+                self.after_block_comment = true;
+            }
+            token => {
+                self.begin();
+                match token {
+                    Token::StringValue(value) | Token::Identifier(value) => {
+                        self.output.push('"');
+                        self.output.push_str(value);
+                        self.output.push('"');
+                    }
+                    Token::Number(value) => self.output.push_str(value),
+                    Token::Boolean(value) => {
+                        self.output.push_str(if *value { "true" } else { "false" })
+                    }
+                    Token::Null => self.output.push_str("null"),
+                    _ => (),
+                }
+            }
+        }
+        if !is_comment(token) {
+            self.after_value = ends_value(token);
+            self.value_end = self.output.len();
+            // 最後の要素の後ろにcommaを置く
+            if self.after_value && self.options.trailing_commas && is_closing(next) {
+                self.output.push(',');
+            }
+        }
+    }
+
+    /// 改行か空白を出力してから、次のtokenを書く
+    fn begin(&mut self) {
+        if self.pending_break {
+            self.write_break();
+        } else if self.pending_space {
+            self.output.push(' ');
+        }
+        self.pending_break = false;
+        self.pending_space = false;
+        self.after_open = false;
+    }
+
+    /// 入力で行頭にあったコメントは行頭に置く
+    /// 行の途中の行コメントは直前のtokenと同じ行に、ブロックコメントは直後のtokenと同じ行に置く
+    fn begin_comment(&mut self, newline: bool, line: bool) {
+        if newline || self.output.is_empty() {
+            self.pending_break = true;
+        } else if line || !self.pending_break {
+            self.pending_break = false;
+            self.pending_space = true;
+        }
+        self.begin();
+    }
+
+    fn write_break(&mut self) {
+        if !self.output.is_empty() {
+            self.output.push('\n');
+            self.output
+                .push_str(&" ".repeat(self.options.indent * self.level));
+        }
+    }
+}
+
+fn is_comment(token: &Token) -> bool {
+    matches!(token, Token::CommentLine(_) | Token::CommentBlock(_))
+}
+
+fn is_closing(token: Option<&Token>) -> bool {
+    matches!(token, Some(Token::CloseBrace | Token::CloseBracket))
+}
+
+/// コメントを除いた次のtoken
+fn next_grammar(items: &[Item]) -> Option<&Token> {
+    items
+        .iter()
+        .map(|(token, _)| token)
+        .find(|token| !is_comment(token))
+}
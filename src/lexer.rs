@@ -1,94 +1,185 @@
-use crate::token::{LexerError, Location, Token};
-use crate::utils::is_number_token_char;
-use anyhow::Result;
+use crate::options::ParseOptions;
+use crate::token::{LexerError, Location, Position, Spanned, Token};
+use crate::utils::{
+    hex_to_decimal, is_identifier_char, is_identifier_start_char, is_json_number,
+    is_non_finite_number, is_number_token_char, normalize_json5_number,
+};
 use std::iter::{Enumerate, Peekable};
 use std::str::Chars;
 
+type Result<T> = std::result::Result<T, LexerError>;
+
 pub struct Lexer<'a> {
     input: Peekable<Enumerate<Chars<'a>>>,
+    length: usize,
+    byte_length: usize,
+    line_starts: Vec<usize>, // 各行の先頭のindex
+    options: ParseOptions,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(input: &'a str) -> Self {
+        Self::with_options(input, ParseOptions::default())
+    }
+
+    pub fn with_options(input: &'a str, options: ParseOptions) -> Self {
+        let mut length = 0;
+        let mut line_starts = vec![0];
+        let mut chars = input.chars().enumerate().peekable();
+        while let Some((index, c)) = chars.next() {
+            length += 1;
+            // `\r\n`は`\n`の後ろを行の先頭とする
+            if c == '\n' || (c == '\r' && chars.peek().is_none_or(|(_, c)| *c != '\n')) {
+                line_starts.push(index + 1);
+            }
+        }
         Lexer {
             input: input.chars().enumerate().peekable(),
+            length,
+            byte_length: input.len(),
+            line_starts,
+            options,
         }
     }
 
-    pub fn tokenize(&mut self) -> Result<Vec<Token>> {
-        let mut tokens = vec![];
+    /// tokenごとに開始・終了のindexを`Spanned`として付与する
+    /// 入力が`max_input_len`を超える場合は、読み取る前にInputTooLarge
+    pub fn tokenize(&mut self) -> Result<Vec<Spanned<Token>>> {
+        if let Some(max) = self.options.max_input_len {
+            if self.byte_length > max {
+                return Err(LexerError::InputTooLarge(max));
+            }
+        }
+        self.collect()
+    }
 
-        while let Some((index, c)) = self.input.next() {
-            match c {
-                '{' => tokens.push(Token::OpenBrace),
-                '}' => tokens.push(Token::CloseBrace),
-                '[' => tokens.push(Token::OpenBracket),
-                ']' => tokens.push(Token::CloseBracket),
-                '"' => {
-                    let token = self.scan_string_token()?;
-                    tokens.push(token);
-                }
-                c if is_number_token_char(c) => {
-                    let token = self.scan_number_token(c)?;
-                    tokens.push(token);
-                }
-                't' => {
-                    let token = self.scan_bool_token(true, index)?;
-                    tokens.push(token);
-                }
-                'f' => {
-                    let token = self.scan_bool_token(false, index)?;
-                    tokens.push(token);
-                }
-                'n' => {
-                    let token = self.scan_null_token(index)?;
-                    tokens.push(token);
-                }
-                ':' => tokens.push(Token::Colon),
-                ',' => tokens.push(Token::Comma),
-                '/' => {
-                    let token = self.scan_comment_token()?;
-                    tokens.push(token);
-                }
-                ' ' => {
-                    let token = self.scan_whitespaces()?;
-                    tokens.push(token);
-                }
-                '\n' => tokens.push(Token::BreakLine),
-                _ => (),
-            };
+    fn scan_token(&mut self, index: usize, c: char) -> Result<Spanned<Token>> {
+        let token = match c {
+            '{' => Token::OpenBrace,
+            '}' => Token::CloseBrace,
+            '[' => Token::OpenBracket,
+            ']' => Token::CloseBracket,
+            '"' => self.scan_string_token('"', index)?,
+            '\'' if self.options.allow_single_quotes => self.scan_string_token('\'', index)?,
+            c if self.options.allow_unquoted_keys && is_identifier_start_char(c) => {
+                self.scan_identifier_token(c)
+            }
+            'I' | 'N' if self.options.allow_non_finite_numbers => {
+                self.scan_non_finite_token(String::new(), c, index)?
+            }
+            '+' if self.options.allow_json5_decimals => self.scan_number_token(c, index)?,
+            c if is_number_token_char(c) => self.scan_number_token(c, index)?,
+            't' => self.scan_bool_token(true, index)?,
+            'f' => self.scan_bool_token(false, index)?,
+            'n' => self.scan_null_token(index)?,
+            ':' => Token::Colon,
+            ',' => Token::Comma,
+            '/' => self.scan_comment_token()?,
+            '#' if self.options.allow_hash_comments => self.scan_line_comment()?,
+            ' ' => self.scan_whitespaces(),
+            '\t' => self.scan_tabs(),
+            '\r' => {
+                // `\r\n`は1つの改行として扱う
+                self.input.next_if(|(_, c)| *c == '\n');
+                Token::BreakLine
+            }
+            '\n' => Token::BreakLine,
+            other => {
+                return Err(LexerError::UnexpectedChar(other, Location(index, index)));
+            }
+        };
+        let end = self.last_consumed_index();
+        let position = self.position_at(index);
+        Ok(Spanned::new(token, Location(index, end), position))
+    }
+
+    pub(crate) fn position_at(&self, index: usize) -> Position {
+        let line = self.line_starts.partition_point(|start| *start <= index);
+        Position {
+            line,
+            column: index - self.line_starts[line - 1] + 1,
         }
+    }
 
-        Ok(tokens)
+    /// 直前に読み取った文字のindex
+    fn last_consumed_index(&mut self) -> usize {
+        match self.input.peek() {
+            Some((index, _)) => index - 1,
+            None => self.length - 1,
+        }
     }
 
-    fn scan_string_token(&mut self) -> Result<Token> {
+    /// `quote`で囲まれた文字列を読み取る
+    /// 出力は常に`"`で囲むため、`'`で囲まれた文字列中の`"`はescapeし、`\'`はescapeを外す
+    /// `start`は開始のquoteのindex
+    fn scan_string_token(&mut self, quote: char, start: usize) -> Result<Token> {
+        let unterminated = LexerError::UnterminatedString(Location(start, start));
         let mut value = String::new();
 
-        while let Some((_index, c)) = self.input.next() {
+        while let Some((index, c)) = self.input.next() {
+            if let Some(max) = self.options.max_string_len {
+                // quoteを除いた、現在の文字より前の文字数
+                if index - start - 1 > max {
+                    return Err(LexerError::StringTooLong(max, Location(start, index)));
+                }
+            }
             match c {
-                '"' => {
+                c if c == quote => {
                     return Ok(Token::StringValue(value));
                 }
+                // U+0000からU+001Fはescapeが必要
+                '\u{0}'..='\u{1f}' => {
+                    return Err(LexerError::UnescapedControlChar(c, Location(index, index)));
+                }
+                '"' => {
+                    value.push_str("\\\"");
+                }
                 '\\' => {
-                    let (_, c2) = self
-                        .input
-                        .next()
-                        .ok_or(LexerError::NotExistTerminalSymbol)?;
+                    let (_, c2) = self.input.next().ok_or(unterminated.clone())?;
                     match c2 {
                         'u' => {
-                            let hex = self.take_chars_with(4);
-                            if hex.len() != 4 && hex.parse::<f64>().is_ok() {
-                                return Err(LexerError::NotExistTerminalSymbol.into());
-                            }
-
+                            let (hex, code) = self.scan_unicode_escape(index)?;
                             value.push_str(&format!("\\u{}", hex));
+                            match code {
+                                // high surrogateの場合、直後にlow surrogateが必要
+                                0xD800..=0xDBFF => {
+                                    let low_index = index + 6;
+                                    let is_escape =
+                                        self.input.next_if(|(_, c)| *c == '\\').is_some()
+                                            && self.input.next_if(|(_, c)| *c == 'u').is_some();
+                                    if !is_escape {
+                                        return Err(LexerError::InvalidUnicodeEscape(Location(
+                                            index,
+                                            index + 5,
+                                        )));
+                                    }
+                                    let (hex, code) = self.scan_unicode_escape(low_index)?;
+                                    if !(0xDC00..=0xDFFF).contains(&code) {
+                                        return Err(LexerError::InvalidUnicodeEscape(Location(
+                                            index,
+                                            low_index + 5,
+                                        )));
+                                    }
+                                    value.push_str(&format!("\\u{}", hex));
+                                }
+                                // 対になるhigh surrogateのないlow surrogate
+                                0xDC00..=0xDFFF => {
+                                    return Err(LexerError::InvalidUnicodeEscape(Location(
+                                        index,
+                                        index + 5,
+                                    )));
+                                }
+                                _ => (),
+                            }
+                        }
+                        '\'' if quote == '\'' => {
+                            value.push(c2);
                         }
                         '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' => {
                             value.push_str(&format!("\\{}", c2));
                         }
                         _ => {
-                            return Err(LexerError::NotEscapeString.into());
+                            return Err(LexerError::NotEscapeString);
                         }
                     }
                 }
@@ -97,22 +188,96 @@ impl<'a> Lexer<'a> {
                 }
             }
         }
-        Err(LexerError::NotExistTerminalSymbol.into())
+        Err(unterminated)
+    }
+
+    /// `\\u`に続く4桁の16進数を読み取る
+    /// `start`は`\\`のindex
+    fn scan_unicode_escape(&mut self, start: usize) -> Result<(String, u32)> {
+        let mut hex = String::new();
+        while hex.len() < 4 {
+            match self.input.next_if(|(_, c)| c.is_ascii_hexdigit()) {
+                Some((_, c)) => hex.push(c),
+                None => {
+                    return Err(LexerError::InvalidUnicodeEscape(Location(
+                        start,
+                        start + 1 + hex.len(),
+                    )));
+                }
+            }
+        }
+        // 16進数であることは確認済みなのでunwrap
+        let code = u32::from_str_radix(&hex, 16).unwrap();
+        Ok((hex, code))
     }
 
-    fn scan_number_token(&mut self, first: char) -> Result<Token> {
+    fn scan_number_token(&mut self, first: char, index: usize) -> Result<Token> {
         let mut value = String::new();
         value.push(first);
 
         while let Some((_index, c)) = self.input.peek() {
-            if is_number_token_char(*c) {
+            if self.options.allow_hex_numbers
+                && matches!(c, 'x' | 'X')
+                && matches!(value.as_str(), "0" | "-0")
+            {
+                let (_, x) = self.input.next().unwrap();
+                value.push(x);
+                return self.scan_hex_digits(value, index);
+            }
+            if self.options.allow_non_finite_numbers && *c == 'I' && value == "-" {
+                let (_, c) = self.input.next().unwrap();
+                return self.scan_non_finite_token(value, c, index);
+            }
+            // `+`は指数の`e`/`E`の直後のみ数値の一部になる
+            let exponent_sign = *c == '+' && value.ends_with(['e', 'E']);
+            if is_number_token_char(*c) || exponent_sign {
                 let (_, c) = self.input.next().unwrap();
                 value.push(c);
             } else {
-                return Ok(Token::Number(value));
+                return self.normalize_number(value, index);
             }
         }
-        Err(LexerError::NotExistTerminalSymbol.into())
+        self.normalize_number(value, index)
+    }
+
+    /// JSON5の小数点と`+`を許容する場合は、JSONの数値に正規化する
+    /// それ以外の場合、JSONの数値の文法に沿わない値はInvalidNumber
+    fn normalize_number(&mut self, value: String, index: usize) -> Result<Token> {
+        if !self.options.allow_json5_decimals {
+            if !is_json_number(&value) {
+                let location = Location(index, self.last_consumed_index());
+                return Err(LexerError::InvalidNumber(value, location));
+            }
+            return Ok(Token::Number(value));
+        }
+        match normalize_json5_number(&value) {
+            Some(number) => Ok(Token::Number(number)),
+            None => {
+                let location = Location(index, self.last_consumed_index());
+                Err(LexerError::InvalidChars(value, location))
+            }
+        }
+    }
+
+    /// `0x`の後ろの16進数を読み取り、10進数のNumberにする
+    /// `0x`のみや`0xG`のような16進数でない文字はInvalidChars
+    fn scan_hex_digits(&mut self, prefix: String, index: usize) -> Result<Token> {
+        let mut digits = String::new();
+        while let Some((_, c)) = self.input.next_if(|(_, c)| c.is_ascii_alphanumeric()) {
+            digits.push(c);
+        }
+        let decimal = match hex_to_decimal(&digits) {
+            Some(decimal) if !digits.is_empty() => decimal,
+            _ => {
+                let location = Location(index, self.last_consumed_index());
+                return Err(LexerError::InvalidChars(prefix + &digits, location));
+            }
+        };
+        Ok(Token::Number(if prefix.starts_with('-') {
+            format!("-{}", decimal)
+        } else {
+            decimal
+        }))
     }
 
     fn scan_bool_token(&mut self, expect_bool: bool, index: usize) -> Result<Token> {
@@ -130,7 +295,7 @@ impl<'a> Lexer<'a> {
         match &s as &str {
             "true" => Ok(Token::Boolean(true)),
             "false" => Ok(Token::Boolean(false)),
-            other => Err(LexerError::InvalidChars(other.to_string(), location).into()),
+            other => Err(LexerError::InvalidChars(other.to_string(), location)),
         }
     }
 
@@ -141,8 +306,50 @@ impl<'a> Lexer<'a> {
         if s == "null" {
             Ok(Token::Null)
         } else {
-            Err(LexerError::InvalidChars(s.to_string(), location).into())
+            Err(LexerError::InvalidChars(s.to_string(), location))
+        }
+    }
+
+    fn scan_identifier_token(&mut self, first: char) -> Token {
+        let value = self.scan_word(String::new(), first);
+        match &value as &str {
+            "true" => Token::Boolean(true),
+            "false" => Token::Boolean(false),
+            "null" => Token::Null,
+            "Infinity" | "NaN" if self.options.allow_non_finite_numbers => Token::Number(value),
+            _ => Token::Identifier(value),
+        }
+    }
+
+    /// `Infinity`/`-Infinity`/`NaN`を読み取る
+    /// `sign`は読み取り済みの`-`
+    fn scan_non_finite_token(&mut self, sign: String, first: char, index: usize) -> Result<Token> {
+        let value = self.scan_word(sign, first);
+        if is_non_finite_number(&value) {
+            Ok(Token::Number(value))
+        } else {
+            let location = Location(index, self.last_consumed_index());
+            Err(LexerError::InvalidChars(value, location))
+        }
+    }
+
+    /// `prefix`に続けて、識別子に使える文字を読み取る
+    fn scan_word(&mut self, prefix: String, first: char) -> String {
+        let mut value = prefix;
+        value.push(first);
+        while let Some((_, c)) = self.input.next_if(|(_, c)| is_identifier_char(*c)) {
+            value.push(c);
+        }
+        value
+    }
+
+    /// 改行の直前か入力の終わりまでを行コメントとして読み取る
+    fn scan_line_comment(&mut self) -> Result<Token> {
+        let mut value = String::new();
+        while let Some((_, c)) = self.input.next_if(|(_, c)| !matches!(c, '\n' | '\r')) {
+            value.push(c);
         }
+        Ok(Token::CommentLine(value))
     }
 
     fn scan_comment_token(&mut self) -> Result<Token> {
@@ -151,84 +358,72 @@ impl<'a> Lexer<'a> {
             .next()
             .ok_or(LexerError::NotExistTerminalSymbol)?;
         match next_char {
-            '/' => {
-                let mut value = String::new();
-                while let Some((_index, c)) = self.input.peek() {
-                    if c == &'\n' {
-                        return Ok(Token::CommentLine(value));
-                    } else {
-                        // peekしてるのでunwrap
-                        let (_, c) = self.input.next().unwrap();
-                        value.push(c);
-                    }
-                }
-            }
+            '/' => self.scan_line_comment(),
             '*' => {
+                // `*/`以外の`*`や`/`はすべてコメントの本文
                 let mut value = String::new();
-                let mut asterisk_buffer = String::new();
-                let mut prev_asterisk = false;
                 while let Some((_index, c)) = self.input.next() {
-                    match c {
-                        '*' => {
-                            prev_asterisk = true;
-                            asterisk_buffer.push(c);
-                        }
-                        '/' => {
-                            if prev_asterisk {
-                                return Ok(Token::CommentBlock(value));
-                            }
-                        }
-                        _ => {
-                            if prev_asterisk {
-                                value.push_str(&asterisk_buffer);
-                                asterisk_buffer.clear();
-                            }
-                            prev_asterisk = false;
-                            value.push(c);
-                        }
-                    };
+                    if c == '*' && self.input.next_if(|(_, c)| *c == '/').is_some() {
+                        return Ok(Token::CommentBlock(value));
+                    }
+                    value.push(c);
                 }
+                Err(LexerError::UnterminatedComment(Location(
+                    second_slash - 1,
+                    second_slash,
+                )))
             }
-            c => {
-                return Err(LexerError::InvalidChars(
-                    format!("/{}", c).to_string(),
-                    Location(second_slash, second_slash + 1),
-                )
-                .into())
-            }
+            c => Err(LexerError::InvalidChars(
+                format!("/{}", c).to_string(),
+                Location(second_slash, second_slash + 1),
+            )),
         }
-        Err(LexerError::NotExistTerminalSymbol.into())
     }
 
-    fn scan_whitespaces(&mut self) -> Result<Token> {
+    fn scan_whitespaces(&mut self) -> Token {
         let mut length: usize = 1; // 呼び出し時点で1
-        while let Some((_index, c)) = self.input.peek() {
-            let c = *c;
-            match c {
-                ' ' => {
-                    self.input.next().unwrap();
-                    length += 1
-                }
-                _ => {
-                    return Ok(Token::WhiteSpaces(length as i32));
-                }
-            }
+        while self.input.next_if(|(_, c)| *c == ' ').is_some() {
+            length += 1;
+        }
+        Token::WhiteSpaces(length as i32)
+    }
+
+    fn scan_tabs(&mut self) -> Token {
+        let mut length: usize = 1; // 呼び出し時点で1
+        while self.input.next_if(|(_, c)| *c == '\t').is_some() {
+            length += 1;
         }
-        Err(LexerError::NotExistTerminalSymbol.into())
+        Token::Tabs(length as i32)
     }
 
     fn take_chars_with(&mut self, times: i32) -> String {
-        let chars = (0..times)
+        (0..times)
             .filter_map(|_| self.input.next().map(|(_index, c)| c))
-            .collect::<String>();
-        chars
+            .collect::<String>()
+    }
+}
+
+/// tokenを1つずつ読み取る
+/// エラーの後も続きから読み取れる
+impl Iterator for Lexer<'_> {
+    type Item = Result<Spanned<Token>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, c) = self.input.next()?;
+        Some(self.scan_token(index, c))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::token::Token;
+    use crate::options::ParseOptions;
+    use crate::token::{LexerError, Location, Position, Spanned, Token};
+
+    /// 位置を除いたtokenの配列
+    fn values(tokens: Vec<Spanned<Token>>) -> Vec<Token> {
+        tokens.into_iter().map(|token| token.value).collect()
+    }
 
     #[test]
     fn lexer_should_success_scan() {
@@ -244,7 +439,7 @@ mod tests {
      */
 }"#,
         );
-        let result = lexer.tokenize().expect("lexerは配列を返します。");
+        let result = values(lexer.tokenize().expect("lexerは配列を返します。"));
         let expected = [
             Token::OpenBrace,
             Token::BreakLine,
@@ -294,13 +489,79 @@ mod tests {
         assert_eq!(36, result.len(), "token配列長が想定外です。");
     }
 
+    #[test]
+    fn tokenize_should_attach_locations() {
+        let mut lexer = Lexer::new("{\"a\": [true, -1.5]} // c\r\n\t");
+        let result = lexer.tokenize().expect("lexerは配列を返します。");
+        let locations: Vec<(Token, Location)> = result
+            .into_iter()
+            .map(|token| (token.value, token.location))
+            .collect();
+        assert_eq!(
+            vec![
+                (Token::OpenBrace, Location(0, 0)),
+                (Token::StringValue("a".to_string()), Location(1, 3)),
+                (Token::Colon, Location(4, 4)),
+                (Token::WhiteSpaces(1), Location(5, 5)),
+                (Token::OpenBracket, Location(6, 6)),
+                (Token::Boolean(true), Location(7, 10)),
+                (Token::Comma, Location(11, 11)),
+                (Token::WhiteSpaces(1), Location(12, 12)),
+                (Token::Number("-1.5".to_string()), Location(13, 16)),
+                (Token::CloseBracket, Location(17, 17)),
+                (Token::CloseBrace, Location(18, 18)),
+                (Token::WhiteSpaces(1), Location(19, 19)),
+                (Token::CommentLine(" c".to_string()), Location(20, 23)),
+                (Token::BreakLine, Location(24, 25)),
+                (Token::Tabs(1), Location(26, 26)),
+            ],
+            locations
+        );
+    }
+
+    #[test]
+    fn tokenize_should_attach_positions() {
+        let mut lexer = Lexer::new("{\r\n  \"a\": 1,\r\"b\"\n}");
+        let positions: Vec<(Token, Position)> = lexer
+            .tokenize()
+            .expect("lexerは配列を返します。")
+            .into_iter()
+            .filter(|token| !matches!(token.value, Token::WhiteSpaces(_) | Token::BreakLine))
+            .map(|token| (token.value, token.position))
+            .collect();
+        let at = |line, column| Position { line, column };
+        assert_eq!(
+            vec![
+                (Token::OpenBrace, at(1, 1)),
+                (Token::StringValue("a".to_string()), at(2, 3)),
+                (Token::Colon, at(2, 6)),
+                (Token::Number("1".to_string()), at(2, 8)),
+                (Token::Comma, at(2, 9)),
+                (Token::StringValue("b".to_string()), at(3, 1)),
+                (Token::CloseBrace, at(4, 1)),
+            ],
+            positions
+        );
+    }
+
+    #[test]
+    fn tokenize_should_err_with_unexpected_char() {
+        let mut lexer = Lexer::new(r#"{"a": @}"#);
+        let err = lexer.tokenize().expect_err("`@`はErrになります。");
+        assert_eq!(LexerError::UnexpectedChar('@', Location(6, 6)), err);
+
+        let mut lexer = Lexer::new("[“a”]");
+        let err = lexer.tokenize().expect_err("`“`はErrになります。");
+        assert_eq!(LexerError::UnexpectedChar('“', Location(1, 1)), err);
+    }
+
     #[test]
     fn scan_string_token_should_return_token() {
         let mut lexer = Lexer::new(r#""name123""#);
         // 最初の"まで進める
         lexer.input.next();
         let token = lexer
-            .scan_string_token()
+            .scan_string_token('"', 0)
             .expect("[scan_string_token_should_return_token]\"name\"のscanに失敗しました。");
         assert_eq!(Token::StringValue("name123".to_string()), token);
 
@@ -308,7 +569,7 @@ mod tests {
         // 最初の"まで進める
         lexer.input.next();
         let token = lexer
-            .scan_string_token()
+            .scan_string_token('"', 0)
             .expect("[scan_string_token_should_return_token]\"あいうえお\"のscanに失敗しました。");
         assert_eq!(Token::StringValue("あいうえお".to_string()), token);
 
@@ -316,7 +577,7 @@ mod tests {
         // 最初の"まで進める
         lexer.input.next();
         let token = lexer
-            .scan_string_token()
+            .scan_string_token('"', 0)
             .expect("[scan_string_token_should_return_token]\"あいうabc\"のscanに失敗しました。");
         assert_eq!(
             Token::StringValue("\\u3042\\u3044\\u3046abc".to_string()),
@@ -327,7 +588,7 @@ mod tests {
         // 最初の"まで進める
         lexer.input.next();
         let token = lexer
-            .scan_string_token()
+            .scan_string_token('"', 0)
             .expect("[scan_string_token_should_return_token]\"😀👍\"のscanに失敗しました。");
         assert_eq!(
             Token::StringValue("\\ud83d\\ude00\\ud83d\\udc4d".to_string()),
@@ -338,7 +599,7 @@ mod tests {
         // 最初の"まで進める
         lexer.input.next();
         let token = lexer
-            .scan_string_token()
+            .scan_string_token('"', 0)
             .expect("[scan_string_token_should_return_token]\"😀👍\"のscanに失敗しました。");
         assert_eq!(Token::StringValue("😀👍".to_string()), token);
 
@@ -346,7 +607,7 @@ mod tests {
         // 最初の"まで進める
         lexer.input.next();
         let token = lexer
-            .scan_string_token()
+            .scan_string_token('"', 0)
             .expect(r#"[scan_string_token_should_return_token]"test\"\/\\\b\n\f\r\t""のscanに失敗しました。"#);
         assert_eq!(
             Token::StringValue(r#"test\"\/\\\b\n\f\r\t"#.to_string()),
@@ -354,11 +615,310 @@ mod tests {
         );
     }
 
+    #[test]
+    fn scan_string_token_should_validate_unicode_escape() {
+        let mut lexer = Lexer::new(r#""\uD83D\uDE00""#);
+        // 最初の"まで進める
+        lexer.input.next();
+        let token = lexer
+            .scan_string_token('"', 0)
+            .expect("[scan_string_token_should_validate_unicode_escape]surrogate pairのscanに失敗しました。");
+        assert_eq!(Token::StringValue("\\uD83D\\uDE00".to_string()), token);
+
+        let cases = [
+            (r#""\uZZZZ""#, Location(1, 2)),
+            (r#""\u12""#, Location(1, 4)),
+            (r#""\uD83D""#, Location(1, 6)),
+            (r#""\uD83Dabc""#, Location(1, 6)),
+            (r#""\uD83D\u0041""#, Location(1, 12)),
+            (r#""\uDE00""#, Location(1, 6)),
+        ];
+        for (input, location) in cases {
+            let mut lexer = Lexer::new(input);
+            // 最初の"まで進める
+            lexer.input.next();
+            let err = lexer.scan_string_token('"', 0).expect_err(input);
+            assert_eq!(LexerError::InvalidUnicodeEscape(location), err, "{}", input);
+        }
+    }
+
+    #[test]
+    fn tokenize_should_scan_single_quoted_string() {
+        let mut lexer = Lexer::with_options(
+            r#"['it\'s "ok"', "a'b"]"#,
+            ParseOptions::new().allow_single_quotes(true),
+        );
+        let result = values(lexer.tokenize().expect("lexerは配列を返します。"));
+        assert_eq!(
+            vec![
+                Token::OpenBracket,
+                Token::StringValue(r#"it's \"ok\""#.to_string()),
+                Token::Comma,
+                Token::WhiteSpaces(1),
+                Token::StringValue("a'b".to_string()),
+                Token::CloseBracket,
+            ],
+            result
+        );
+
+        // 既定では許容しない
+        let mut lexer = Lexer::new("['a']");
+        let err = lexer.tokenize().expect_err("`'`はErrになります。");
+        assert_eq!(LexerError::UnexpectedChar('\'', Location(1, 1)), err);
+    }
+
+    #[test]
+    fn tokenize_should_scan_identifiers() {
+        let mut lexer = Lexer::with_options(
+            r#"{name: "sato", $_e1: true, trueish: null}"#,
+            ParseOptions::new().allow_unquoted_keys(true),
+        );
+        let result = values(lexer.tokenize().expect("lexerは配列を返します。"));
+        assert_eq!(
+            vec![
+                Token::OpenBrace,
+                Token::Identifier("name".to_string()),
+                Token::Colon,
+                Token::WhiteSpaces(1),
+                Token::StringValue("sato".to_string()),
+                Token::Comma,
+                Token::WhiteSpaces(1),
+                Token::Identifier("$_e1".to_string()),
+                Token::Colon,
+                Token::WhiteSpaces(1),
+                Token::Boolean(true),
+                Token::Comma,
+                Token::WhiteSpaces(1),
+                Token::Identifier("trueish".to_string()),
+                Token::Colon,
+                Token::WhiteSpaces(1),
+                Token::Null,
+                Token::CloseBrace,
+            ],
+            result
+        );
+
+        // 既定では許容しない
+        let mut lexer = Lexer::new("{name: 1}");
+        assert!(lexer.tokenize().is_err());
+    }
+
     #[test]
     fn scan_string_token_should_err() {
         // 部分的なテストのためのinvalid json
         let mut lexer = Lexer::new("name");
-        assert!(lexer.scan_string_token().is_err());
+        assert!(lexer.scan_string_token('"', 0).is_err());
+
+        for input in ["[\"abc", "[\"abc\\"] {
+            let err = Lexer::new(input).tokenize().expect_err(input);
+            assert_eq!(
+                LexerError::UnterminatedString(Location(1, 1)),
+                err,
+                "{}",
+                input
+            );
+        }
+        let err = Lexer::new("[1, /* a */ /* b *").tokenize().unwrap_err();
+        assert_eq!(LexerError::UnterminatedComment(Location(12, 13)), err);
+    }
+
+    #[test]
+    fn tokenize_should_limit_input_and_string_length() {
+        let options = ParseOptions::new().max_input_len(7);
+        assert!(Lexer::with_options("[\"あ\"]", options).tokenize().is_ok());
+        let err = Lexer::with_options("[\"あ\" ]", options)
+            .tokenize()
+            .unwrap_err();
+        assert_eq!(LexerError::InputTooLarge(7), err);
+
+        let options = ParseOptions::new().max_string_len(3);
+        assert!(Lexer::with_options(r#"["abc", "\n"]"#, options)
+            .tokenize()
+            .is_ok());
+        let err = Lexer::with_options(r#"["abcd"]"#, options)
+            .tokenize()
+            .unwrap_err();
+        assert_eq!(LexerError::StringTooLong(3, Location(1, 6)), err);
+    }
+
+    #[test]
+    fn tokenize_should_end_line_comment_at_eof() {
+        let mut lexer = Lexer::new("[1]\n// trailing");
+        let result = values(
+            lexer
+                .tokenize()
+                .expect("入力の終わりも行コメントの終わりです。"),
+        );
+        assert_eq!(
+            vec![
+                Token::OpenBracket,
+                Token::Number("1".to_string()),
+                Token::CloseBracket,
+                Token::BreakLine,
+                Token::CommentLine(" trailing".to_string()),
+            ],
+            result
+        );
+    }
+
+    #[test]
+    fn tokenize_should_reject_number_without_digits() {
+        for (input, value, location) in [
+            ("[-]", "-", Location(1, 1)),
+            ("[1, -, 2]", "-", Location(4, 4)),
+            ("[-.]", "-.", Location(1, 2)),
+            ("[-e5]", "-e5", Location(1, 3)),
+            ("[--1]", "--1", Location(1, 3)),
+            ("[e1]", "e1", Location(1, 2)),
+            ("[1-2]", "1-2", Location(1, 3)),
+            ("[1.2.3]", "1.2.3", Location(1, 5)),
+            ("[1e]", "1e", Location(1, 2)),
+            ("[1.]", "1.", Location(1, 2)),
+            ("[01]", "01", Location(1, 2)),
+        ] {
+            let err = Lexer::new(input).tokenize().expect_err(input);
+            assert_eq!(
+                LexerError::InvalidNumber(value.to_string(), location),
+                err,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn tokenize_should_scan_hex_number() {
+        let options = ParseOptions::new().allow_hex_numbers(true);
+        let mut lexer = Lexer::with_options("[0xFF,-0X1a]", options);
+        let result = values(lexer.tokenize().expect("lexerは配列を返します。"));
+        assert_eq!(
+            vec![
+                Token::OpenBracket,
+                Token::Number("255".to_string()),
+                Token::Comma,
+                Token::Number("-26".to_string()),
+                Token::CloseBracket,
+            ],
+            result
+        );
+
+        for (input, invalid) in [("[0x]", "0x"), ("[0xG]", "0xG"), ("[-0x1g]", "-0x1g")] {
+            let mut lexer = Lexer::with_options(input, options);
+            let err = lexer
+                .tokenize()
+                .expect_err("16進数でない文字はErrになります。");
+            assert_eq!(
+                LexerError::InvalidChars(invalid.to_string(), Location(1, invalid.len())),
+                err
+            );
+        }
+
+        // 既定では許容しない
+        let mut lexer = Lexer::new("[0xFF]");
+        let err = lexer.tokenize().expect_err("`x`はErrになります。");
+        assert_eq!(LexerError::UnexpectedChar('x', Location(2, 2)), err);
+    }
+
+    #[test]
+    fn tokenize_should_scan_non_finite_numbers() {
+        let options = ParseOptions::new().allow_non_finite_numbers(true);
+        let mut lexer = Lexer::with_options("[NaN,null,Infinity,-Infinity]", options);
+        let result = values(lexer.tokenize().expect("lexerは配列を返します。"));
+        assert_eq!(
+            vec![
+                Token::OpenBracket,
+                Token::Number("NaN".to_string()),
+                Token::Comma,
+                Token::Null,
+                Token::Comma,
+                Token::Number("Infinity".to_string()),
+                Token::Comma,
+                Token::Number("-Infinity".to_string()),
+                Token::CloseBracket,
+            ],
+            result
+        );
+
+        for (input, invalid) in [
+            ("[Nan]", "Nan"),
+            ("[-Inf]", "-Inf"),
+            ("[Infinity1]", "Infinity1"),
+        ] {
+            let mut lexer = Lexer::with_options(input, options);
+            let err = lexer.tokenize().expect_err("不正な綴りはErrになります。");
+            assert_eq!(
+                LexerError::InvalidChars(invalid.to_string(), Location(1, invalid.len())),
+                err
+            );
+        }
+
+        // 既定では許容しない
+        let mut lexer = Lexer::new("[NaN]");
+        let err = lexer.tokenize().expect_err("`N`はErrになります。");
+        assert_eq!(LexerError::UnexpectedChar('N', Location(1, 1)), err);
+    }
+
+    #[test]
+    fn tokenize_should_normalize_json5_decimals() {
+        let options = ParseOptions::new().allow_json5_decimals(true);
+        let mut lexer = Lexer::with_options("[.5,5.,+5,+.5e2]", options);
+        let result = values(lexer.tokenize().expect("lexerは配列を返します。"));
+        assert_eq!(
+            vec![
+                Token::OpenBracket,
+                Token::Number("0.5".to_string()),
+                Token::Comma,
+                Token::Number("5".to_string()),
+                Token::Comma,
+                Token::Number("5".to_string()),
+                Token::Comma,
+                Token::Number("0.5e2".to_string()),
+                Token::CloseBracket,
+            ],
+            result
+        );
+
+        let mut lexer = Lexer::with_options("[+.]", options);
+        let err = lexer
+            .tokenize()
+            .expect_err("数値にならない場合はErrになります。");
+        assert_eq!(
+            LexerError::InvalidChars("+.".to_string(), Location(1, 2)),
+            err
+        );
+
+        // 既定では`+`を許容しない
+        let mut lexer = Lexer::new("[+5]");
+        let err = lexer.tokenize().expect_err("`+`はErrになります。");
+        assert_eq!(LexerError::UnexpectedChar('+', Location(1, 1)), err);
+    }
+
+    #[test]
+    fn tokenize_should_scan_hash_comment() {
+        let options = ParseOptions::new().allow_hash_comments(true);
+        let mut lexer = Lexer::with_options("{ \"a\": 1 # note\n }", options);
+        let result = values(lexer.tokenize().expect("lexerは配列を返します。"));
+        assert_eq!(
+            vec![
+                Token::OpenBrace,
+                Token::WhiteSpaces(1),
+                Token::StringValue("a".to_string()),
+                Token::Colon,
+                Token::WhiteSpaces(1),
+                Token::Number("1".to_string()),
+                Token::WhiteSpaces(1),
+                Token::CommentLine(" note".to_string()),
+                Token::BreakLine,
+                Token::WhiteSpaces(1),
+                Token::CloseBrace,
+            ],
+            result
+        );
+
+        // 既定では許容しない
+        let mut lexer = Lexer::new("{ \"a\": 1 # note\n }");
+        let err = lexer.tokenize().expect_err("`#`はErrになります。");
+        assert_eq!(LexerError::UnexpectedChar('#', Location(9, 9)), err);
     }
 
     #[test]
@@ -368,7 +928,7 @@ mod tests {
         // 最初の`"`まで進める
         lexer.input.next();
         let (_, first) = lexer.input.next().unwrap();
-        if let Ok(token) = lexer.scan_number_token(first) {
+        if let Ok(token) = lexer.scan_number_token(first, 1) {
             assert_eq!(Token::Number("100".to_string()), token);
         } else {
             panic!("[scan_string_token]がErrを返しました。");
@@ -376,13 +936,54 @@ mod tests {
     }
 
     #[test]
-    fn scan_number_token_should_err() {
-        // 部分的なテストのためのinvalid json
+    fn scan_number_token_should_accept_exponent_sign() {
+        for (input, expected) in [(":1e+5,", "1e+5"), (":0E+1,", "0E+1"), (":1e-5,", "1e-5")] {
+            let mut lexer = Lexer::new(input);
+            lexer.input.next();
+            let (_, first) = lexer.input.next().unwrap();
+            assert_eq!(
+                Ok(Token::Number(expected.to_string())),
+                lexer.scan_number_token(first, 1),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn scan_number_token_should_end_at_eof() {
         let mut lexer = Lexer::new(":100");
-        // 最初の`"`まで進める
         lexer.input.next();
         let (_, first) = lexer.input.next().unwrap();
-        assert!(lexer.scan_number_token(first).is_err());
+        assert_eq!(
+            Ok(Token::Number("100".to_string())),
+            lexer.scan_number_token(first, 1)
+        );
+        let mut lexer = Lexer::new(":1e");
+        lexer.input.next();
+        let (_, first) = lexer.input.next().unwrap();
+        assert_eq!(
+            Err(LexerError::InvalidNumber("1e".to_string(), Location(1, 2))),
+            lexer.scan_number_token(first, 1)
+        );
+    }
+
+    #[test]
+    fn scan_string_token_should_reject_control_chars() {
+        for (input, c) in [
+            ("[\"a\tb\"]", '\t'),
+            ("[\"a\nb\"]", '\n'),
+            ("[\"\u{0}\"]", '\u{0}'),
+        ] {
+            let err = Lexer::new(input).tokenize().expect_err(input);
+            let index = input.chars().position(|x| x == c).unwrap();
+            assert_eq!(
+                LexerError::UnescapedControlChar(c, Location(index, index)),
+                err,
+                "{:?}",
+                input
+            );
+        }
     }
 
     #[test]
@@ -500,6 +1101,30 @@ test comment
         };
     }
 
+    #[test]
+    fn scan_comment_token_should_keep_asterisks_in_block() {
+        for (input, expected) in [
+            ("/**/", ""),
+            ("/***/", "*"),
+            ("/** a ** b **/", "* a ** b *"),
+            ("/* a/b * / */", " a/b * / "),
+        ] {
+            let mut lexer = Lexer::new(input);
+            lexer.input.next().unwrap();
+            assert_eq!(
+                Token::CommentBlock(expected.to_string()),
+                lexer
+                    .scan_comment_token()
+                    .expect("コメントを読み取れます。"),
+                "`{}`",
+                input
+            );
+        }
+        let mut lexer = Lexer::new("/* a *");
+        lexer.input.next().unwrap();
+        assert!(lexer.scan_comment_token().is_err());
+    }
+
     #[test]
     fn scan_comment_token_should_err() {
         // 部分的なテストのためのinvalid json
@@ -514,18 +1139,53 @@ test comment
         let mut lexer = Lexer::new(r#"   ""#);
         // 最初の` `まで進める
         lexer.input.next();
-        if let Ok(token) = lexer.scan_whitespaces() {
-            assert_eq!(Token::WhiteSpaces(3), token);
+        assert_eq!(Token::WhiteSpaces(3), lexer.scan_whitespaces());
+    }
+
+    #[test]
+    fn lexer_should_scan_tabs_and_carriage_returns() {
+        let mut lexer = Lexer::new("{\r\n\t\t\"a\": 1, // comment\r\n\t\"b\": 2\r}");
+        let result = values(lexer.tokenize().expect("lexerは配列を返します。"));
+        let expected = vec![
+            Token::OpenBrace,
+            Token::BreakLine,
+            Token::Tabs(2),
+            Token::StringValue("a".to_string()),
+            Token::Colon,
+            Token::WhiteSpaces(1),
+            Token::Number("1".to_string()),
+            Token::Comma,
+            Token::WhiteSpaces(1),
+            Token::CommentLine(" comment".to_string()),
+            Token::BreakLine,
+            Token::Tabs(1),
+            Token::StringValue("b".to_string()),
+            Token::Colon,
+            Token::WhiteSpaces(1),
+            Token::Number("2".to_string()),
+            Token::BreakLine,
+            Token::CloseBrace,
+        ];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn scan_comment_line_token_should_stop_at_carriage_return() {
+        // 部分的なテストのためのinvalid json
+        let mut lexer = Lexer::new("// comment\r\n");
+        // 最初の`/`まで進める
+        lexer.input.next();
+        if let Ok(token) = lexer.scan_comment_token() {
+            assert_eq!(Token::CommentLine(" comment".to_string()), token);
         } else {
-            panic!("[scan_whitespaces]がErrを返しました。");
+            panic!("[scan_comment_token]がErrを返しました。");
         };
     }
 
     #[test]
-    fn scan_whitespaces_token_should_err() {
-        // 部分的なテストのためのinvalid json
+    fn scan_whitespaces_token_should_end_at_eof() {
         let mut lexer = Lexer::new(r#"  "#);
         lexer.input.next().unwrap();
-        assert!(lexer.scan_whitespaces().is_err());
+        assert_eq!(Token::WhiteSpaces(2), lexer.scan_whitespaces());
     }
 }
@@ -1,19 +1,746 @@
 extern crate wasm_bindgen;
-use wasm_bindgen::prelude::*;
+use crate::comment::Comments;
+use crate::format::FormatOptions;
 use crate::lexer::Lexer;
+use crate::options::ParseOptions;
 use crate::parser::Parser;
+use crate::serializer::SerializeOptions;
+use crate::token::{Position, Spanned, Token};
+use wasm_bindgen::prelude::*;
 
-mod lexer;
-mod node;
-mod parser;
-mod token;
+pub mod comment;
+#[cfg(test)]
+mod conformance;
+pub mod encoding;
+pub mod escape;
+pub mod format;
+pub mod lexer;
+pub mod lint;
+mod minify;
+pub mod node;
+pub mod options;
+pub mod parser;
+pub mod path;
+#[cfg(feature = "serde")]
+mod serde_impl;
+pub mod serializer;
+pub mod token;
 mod utils;
 
+pub use crate::node::Node;
+pub use crate::parser::ParseError;
+#[cfg(feature = "serde")]
+pub use crate::serde_impl::{from_str, FromStrError};
+pub use crate::token::Location;
+
 #[wasm_bindgen(js_name = toJsonString)]
 pub fn to_json_string(data: String) -> Result<String, String> {
-    let mut lexer = Lexer::new(&data);
-    let token = lexer.tokenize().or_else(|e| Err(e.to_string()))?;
-    let mut parser = Parser::new(&token);
-    let res = parser.parse().or_else(|e| Err(e.to_string()))?;
+    to_json_string_with_options(&data, ParseOptions::default())
+}
+
+/// `Infinity`/`-Infinity`/`NaN`はJSONで表現できないためErrを返す
+pub fn to_json_string_with_options(data: &str, options: ParseOptions) -> Result<String, String> {
+    let res = parse_with_options(data, options).map_err(|e| e.to_string())?;
+    if let Some(path) = res.non_finite_number_paths().first() {
+        return Err(format!(
+            "Non-finite number at `{}` can not be represented in JSON",
+            path
+        ));
+    }
     Ok(res.to_json_string())
 }
+
+/// `indent`個の空白でindentした複数行のJSONを返す
+/// `indent`が0の場合は`toJsonString`と同じく1行で出力する
+#[wasm_bindgen(js_name = toJsonStringPretty)]
+pub fn to_json_string_pretty(data: String, indent: usize) -> Result<String, String> {
+    let node = parse(&data).map_err(|e| e.to_string())?;
+    let options = match indent {
+        0 => SerializeOptions::new(),
+        width => SerializeOptions::new().indent(width),
+    };
+    Ok(node.to_json_string_with(&options))
+}
+
+/// `toJsonStringBatch`の1件ごとの結果
+/// `value`と`error`のどちらか一方だけが値を持つ
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchResult(Result<String, String>);
+
+#[wasm_bindgen]
+impl BatchResult {
+    /// 変換に成功した場合のJSON
+    #[wasm_bindgen(getter)]
+    pub fn value(&self) -> Option<String> {
+        self.0.clone().ok()
+    }
+
+    /// 変換に失敗した場合のエラーメッセージ
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.0.clone().err()
+    }
+}
+
+/// 複数の入力をまとめて`toJsonString`と同じように変換する
+/// 失敗した入力があっても残りの入力は変換し、入力と同じ順序で結果を返す
+#[wasm_bindgen(js_name = toJsonStringBatch)]
+pub fn to_json_string_batch(inputs: Vec<String>) -> Vec<BatchResult> {
+    inputs
+        .into_iter()
+        .map(|input| BatchResult(to_json_string(input)))
+        .collect()
+}
+
+/// `validate`が返す、parseできなかった理由と位置
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    message: String,
+    position: Position,
+}
+
+#[wasm_bindgen]
+impl Diagnostic {
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    /// 1始まりの行
+    #[wasm_bindgen(getter)]
+    pub fn line(&self) -> usize {
+        self.position.line
+    }
+
+    /// 1始まりの列
+    #[wasm_bindgen(getter)]
+    pub fn column(&self) -> usize {
+        self.position.column
+    }
+}
+
+/// JSONを出力せずにparseできるかだけを検証する
+/// parseできれば`undefined`、できなければ最初のエラーを返す
+#[wasm_bindgen]
+pub fn validate(data: &str) -> Option<Diagnostic> {
+    let error = parse(data).err()?;
+    Some(Diagnostic {
+        message: error.to_string(),
+        position: error_position(data, &error),
+    })
+}
+
+/// `input`をparseした時の`error`の位置、位置を持たないエラーは入力の末尾とする
+/// `\r\n`は1つの改行として数える
+pub fn error_position(input: &str, error: &ParseError) -> Position {
+    if let Some(position) = error.position() {
+        return position;
+    }
+    let index = match error {
+        ParseError::Lexer(error) => error.location().map(|location| location.0),
+        _ => None,
+    };
+    Lexer::new(input).position_at(index.unwrap_or(input.chars().count()))
+}
+
+/// `tokenize`が返すtoken、JavaScriptのsyntax highlight向け
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenSpan {
+    kind: &'static str,
+    value: String,
+    start: usize,
+    end: usize,
+}
+
+#[wasm_bindgen]
+impl TokenSpan {
+    /// `openBrace`や`lineComment`などのtokenの種類
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> String {
+        self.kind.to_string()
+    }
+
+    /// quoteやコメントの記号を含む、入力のままの文字列
+    #[wasm_bindgen(getter)]
+    pub fn value(&self) -> String {
+        self.value.clone()
+    }
+
+    /// 開始位置の文字のindex
+    #[wasm_bindgen(getter)]
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// 終了位置の次の文字のindex
+    #[wasm_bindgen(getter)]
+    pub fn end(&self) -> usize {
+        self.end
+    }
+}
+
+/// 空白やコメントを含むすべてのtokenを返す
+/// indexはUTF-16ではなく文字(Unicode scalar value)単位
+#[wasm_bindgen]
+pub fn tokenize(data: &str) -> Result<Vec<TokenSpan>, String> {
+    let tokens = Lexer::new(data).tokenize().map_err(|e| e.to_string())?;
+    let chars: Vec<char> = data.chars().collect();
+    Ok(tokens
+        .into_iter()
+        .map(|token| {
+            let (start, end) = (token.location.0, token.location.1 + 1);
+            TokenSpan {
+                kind: token_kind(&token.value),
+                value: chars[start..end].iter().collect(),
+                start,
+                end,
+            }
+        })
+        .collect())
+}
+
+fn token_kind(token: &Token) -> &'static str {
+    match token {
+        Token::OpenBrace => "openBrace",
+        Token::CloseBrace => "closeBrace",
+        Token::OpenBracket => "openBracket",
+        Token::CloseBracket => "closeBracket",
+        Token::StringValue(_) => "string",
+        Token::Number(_) => "number",
+        Token::Boolean(_) => "boolean",
+        Token::Null => "null",
+        Token::Identifier(_) => "identifier",
+        Token::CommentLine(_) => "lineComment",
+        Token::CommentBlock(_) => "blockComment",
+        Token::Comma => "comma",
+        Token::Colon => "colon",
+        Token::WhiteSpaces(_) => "whitespace",
+        Token::Tabs(_) => "tab",
+        Token::BreakLine => "lineBreak",
+    }
+}
+
+/// コメントを残したままJSONCとして出力する
+/// コメントは直後の値(objectのmemberはkey)の前に出力する
+#[wasm_bindgen(js_name = toJsoncString)]
+pub fn to_jsonc_string(data: String) -> Result<String, String> {
+    let (node, comments) = parse_with_comments(&data).map_err(|e| e.to_string())?;
+    Ok(node.to_jsonc_string(&comments))
+}
+
+/// コメントと空白、末尾のcommaを除いた最小のJSONを返す
+/// keyの順序と数値の表記は入力のまま残す
+/// ```
+/// assert_eq!(Ok(r#"{"b":1.0,"a":2}"#.to_string()), jsonc_wasm::minify("{ \"b\": 1.0, \"a\": 2 }"));
+/// ```
+#[wasm_bindgen]
+pub fn minify(input: &str) -> Result<String, String> {
+    let tokens = Lexer::new(input).tokenize().map_err(|e| e.to_string())?;
+    validate_tokens(&tokens)?;
+    Ok(minify::write_tokens(
+        tokens.into_iter().map(|token| token.value),
+    ))
+}
+
+/// コメントと、コメントの前後の空白だけを除き、それ以外の空白と改行は入力のまま残す
+/// コメントだけの行は改行ごと除く、末尾のcommaは残る
+/// ```
+/// assert_eq!(
+///     Ok("{\n  \"a\": 1\n}".to_string()),
+///     jsonc_wasm::strip_comments("{\n  // head\n  \"a\": 1 // c\n}")
+/// );
+/// ```
+#[wasm_bindgen(js_name = stripComments)]
+pub fn strip_comments(input: &str) -> Result<String, String> {
+    let tokens = Lexer::new(input).tokenize().map_err(|e| e.to_string())?;
+    validate_tokens(&tokens)?;
+    let chars: Vec<char> = input.chars().collect();
+    Ok(minify::strip_comments(&tokens, &chars))
+}
+
+/// tokenを直接書き出す関数のために、parseできるかだけを検証する
+/// tokenのVecは複製せず、parserが読むたびに1つずつcloneする
+fn validate_tokens(tokens: &[Spanned<Token>]) -> Result<(), String> {
+    Parser::new(tokens.iter().cloned())
+        .parse()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// コメントを残したまま、`options`に沿って改行とindentを揃える
+/// Nodeを経由しないため、keyの順序と数値の表記は入力のまま残る
+pub fn format(input: &str, options: FormatOptions) -> Result<String, String> {
+    let tokens = Lexer::new(input).tokenize().map_err(|e| e.to_string())?;
+    validate_tokens(&tokens)?;
+    Ok(format::format_tokens(
+        tokens.into_iter().map(|token| token.value),
+        options,
+    ))
+}
+
+/// lexerとparserを通してNodeを返す
+/// ```
+/// let node = jsonc_wasm::parse(r#"{"a": [1, 2] /* comment */}"#).unwrap();
+/// assert_eq!(r#"{"a":[1,2]}"#, node.to_json_string());
+/// ```
+pub fn parse(input: &str) -> Result<Node, ParseError> {
+    parse_with_options(input, ParseOptions::default())
+}
+
+/// UTF-8のbyte列をparseする
+/// 不正なbyte列の場合は、最初の不正なbyteの位置(byte単位)を持つErrを返す
+/// ```
+/// let error = jsonc_wasm::parse_bytes(b"[\"a\xFF\"]").unwrap_err();
+/// assert_eq!(jsonc_wasm::ParseError::InvalidUtf8(3), error);
+/// ```
+pub fn parse_bytes(input: &[u8]) -> Result<Node, ParseError> {
+    let input = std::str::from_utf8(input).map_err(|e| ParseError::InvalidUtf8(e.valid_up_to()))?;
+    parse(input)
+}
+
+pub fn parse_with_options(input: &str, options: ParseOptions) -> Result<Node, ParseError> {
+    let mut lexer = Lexer::with_options(input, options);
+    let token = lexer.tokenize()?;
+    let mut parser = Parser::with_options(token, options);
+    parser.parse()
+}
+
+/// 連続した複数の値(NDJSONなど)をparseし、出現順に返す
+/// ```
+/// let nodes = jsonc_wasm::parse_many("{\"a\":1}\n// log\n[1,2]").unwrap();
+/// assert_eq!(2, nodes.len());
+/// ```
+pub fn parse_many(input: &str) -> Result<Vec<Node>, ParseError> {
+    let options = ParseOptions::default();
+    let mut lexer = Lexer::with_options(input, options);
+    let token = lexer.tokenize()?;
+    let mut parser = Parser::with_options(token, options);
+    parser.parse_many()
+}
+
+/// 最初のエラーで止まらず、見つかったすべてのエラーを返す
+/// lexerのエラーは不正な文字を読み飛ばして続け、parserのエラーより前に並ぶ
+/// 不正な要素を除いて組み立てられた場合はNodeも返す
+/// ```
+/// let (_, errors) = jsonc_wasm::parse_recovering("[1, :, 2, }, 3]");
+/// assert_eq!(2, errors.len());
+/// ```
+pub fn parse_recovering(input: &str) -> (Option<Node>, Vec<ParseError>) {
+    let options = ParseOptions::default();
+    let mut tokens = vec![];
+    let mut errors = vec![];
+    for token in Lexer::with_options(input, options) {
+        match token {
+            Ok(token) => tokens.push(token),
+            Err(error) => errors.push(error.into()),
+        }
+    }
+    let mut parser = Parser::with_options(tokens, options);
+    let (node, parse_errors) = parser.parse_recovering();
+    errors.extend(parse_errors);
+    (node, errors)
+}
+
+/// コメントをNodeとは別に、JSON Pointerに対応づけて返す
+pub fn parse_with_comments(input: &str) -> Result<(Node, Comments), ParseError> {
+    let options = ParseOptions::default();
+    let mut lexer = Lexer::with_options(input, options);
+    let token = lexer.tokenize()?;
+    let mut parser = Parser::with_options(token, options);
+    parser.parse_with_comments()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::{LexerError, Location, Position, Token};
+
+    #[test]
+    fn parse_should_return_node() {
+        let node = parse(r#"{"a": [1, true], /* comment */}"#).expect("parseできます。");
+        assert_eq!(Some(&Node::Boolean(true)), node.pointer("/a/1"));
+    }
+
+    #[test]
+    fn parse_should_return_typed_error() {
+        assert_eq!(
+            Err(ParseError::Lexer(LexerError::UnexpectedChar(
+                '@',
+                Location(1, 1)
+            ))),
+            parse("[@]")
+        );
+        assert_eq!(
+            Err(ParseError::UnClosedToken(
+                Token::OpenBracket,
+                Position { line: 1, column: 1 }
+            )),
+            parse("[1,\n")
+        );
+        assert_eq!(
+            Err(ParseError::MissingComma(Position { line: 1, column: 4 })),
+            parse_with_options("[1 2]", ParseOptions::new().require_commas(true))
+        );
+    }
+
+    #[test]
+    fn to_jsonc_string_should_keep_comments() {
+        let input = r#"// config
+{
+  /* server */
+  "port": 8080, // http
+  "hosts": [
+    "a", // primary
+    "b"
+    // end of hosts
+  ],
+}
+// eof
+"#;
+        // コメントは直後の値に対応づけるため、`// http`は`"hosts"`の前に出力される
+        let output = to_jsonc_string(input.to_string()).unwrap();
+        assert_eq!(
+            r#"// config
+{// http
+"hosts":["a",// primary
+"b"// end of hosts
+],/* server */"port":8080}// eof
+"#,
+            output
+        );
+        assert_eq!(parse(input), parse(&output));
+        assert_eq!(
+            r#"{"hosts":["a","b"],"port":8080}"#,
+            to_json_string(input.to_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn to_json_string_pretty_should_indent_nested_values() {
+        let input = r#"{"a": [1, {"b": []}], /* c */}"#.to_string();
+        assert_eq!(
+            "{\n  \"a\": [\n    1,\n    {\n      \"b\": []\n    }\n  ]\n}",
+            to_json_string_pretty(input.clone(), 2).unwrap()
+        );
+        assert_eq!(
+            to_json_string(input.clone()),
+            to_json_string_pretty(input, 0)
+        );
+        assert!(to_json_string_pretty("[1,".to_string(), 2).is_err());
+    }
+
+    #[test]
+    fn parse_many_should_return_each_value() {
+        let nodes = parse_many("{\"a\":1}\n{\"b\":2}\n[1,2]").expect("parseできます。");
+        assert_eq!(
+            vec![
+                r#"{"a":1}"#.to_string(),
+                r#"{"b":2}"#.to_string(),
+                "[1,2]".to_string()
+            ],
+            nodes.iter().map(Node::to_json_string).collect::<Vec<_>>()
+        );
+        assert_eq!(Ok(vec![]), parse_many(" // empty\n"));
+        assert_eq!(
+            Err(ParseError::UnexpectedConsumedUpToken(Position {
+                line: 2,
+                column: 5
+            })),
+            parse_many("{\"a\":1}\n{\"b\":")
+        );
+    }
+
+    #[test]
+    fn to_json_string_with_options_should_reject_non_finite_numbers() {
+        let options = ParseOptions::new().allow_non_finite_numbers(true);
+        assert_eq!(
+            Err("Non-finite number at `/a/1` can not be represented in JSON".to_string()),
+            to_json_string_with_options(r#"{"a": [1, NaN]}"#, options)
+        );
+        // Nodeからはリテラルのまま出力する
+        let node = parse_with_options("[Infinity, -Infinity]", options).unwrap();
+        assert_eq!("[Infinity,-Infinity]", node.to_json_string());
+    }
+
+    #[test]
+    fn to_json_string_batch_should_isolate_failures() {
+        let results = to_json_string_batch(vec![
+            "[1, // a\n]".to_string(),
+            "[1,".to_string(),
+            "{\"a\": true,}".to_string(),
+        ]);
+        assert_eq!(
+            vec![
+                (Some("[1]".to_string()), None),
+                (None, Some(to_json_string("[1,".to_string()).unwrap_err())),
+                (Some("{\"a\":true}".to_string()), None),
+            ],
+            results
+                .iter()
+                .map(|result| (result.value(), result.error()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn large_integer_should_round_trip_unchanged() {
+        let digits = "1234567890".repeat(20);
+        let input = format!("[-{}, {}]", digits, digits);
+        assert_eq!(
+            Ok(format!("[-{},{}]", digits, digits)),
+            to_json_string(input)
+        );
+        assert!(to_json_string("[-]".to_string()).is_err());
+    }
+
+    #[test]
+    fn strict_options_should_reject_comments_and_trailing_commas() {
+        for input in [r#"{"a":1,}"#, r#"{ /* c */ "a":1 }"#] {
+            assert!(parse(input).is_ok(), "{}", input);
+        }
+        assert_eq!(
+            Err(ParseError::TrailingCommaNotAllowed(Position {
+                line: 1,
+                column: 7
+            })),
+            parse_with_options(r#"{"a":1,}"#, ParseOptions::strict())
+        );
+        assert_eq!(
+            Err(ParseError::CommentsNotAllowed(Position {
+                line: 1,
+                column: 3
+            })),
+            parse_with_options(r#"{ /* c */ "a":1 }"#, ParseOptions::strict())
+        );
+    }
+
+    #[test]
+    fn unclosed_error_should_point_to_innermost_opener() {
+        let error = parse("{\n  \"a\": {\n    \"b\": [1]\n").unwrap_err();
+        assert_eq!(
+            "Unclosed object opened at line 2, column 8",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn parse_bytes_should_report_invalid_utf8_offset() {
+        assert_eq!(parse("[\"あ\", 1]"), parse_bytes("[\"あ\", 1]".as_bytes()));
+        assert_eq!(
+            Err(ParseError::InvalidUtf8(5)),
+            parse_bytes(b"[\"\xE3\x81\x82\xE3\x81\"]")
+        );
+    }
+
+    #[test]
+    fn validate_should_report_first_error_with_position() {
+        assert_eq!(None, validate("{\"a\": [1, 2], // ok\n}"));
+        let diagnostic = validate("{\n  \"a\": @\n}").expect("`@`はErrになります。");
+        assert_eq!("Unexpected char `@`", diagnostic.message());
+        assert_eq!((2, 8), (diagnostic.line(), diagnostic.column()));
+        let diagnostic = validate("[1,\n\"a").expect("閉じていない文字列はErrになります。");
+        assert_eq!("Unterminated string", diagnostic.message());
+        assert_eq!((2, 1), (diagnostic.line(), diagnostic.column()));
+    }
+
+    #[test]
+    fn parse_recovering_should_report_independent_errors() {
+        let input = "{\n  \"a\": :,\n  \"b\": true,\n  \"c\" 1,\n  \"d\": [1, 2]\n}";
+        let (node, errors) = parse_recovering(input);
+        assert_eq!(
+            vec![
+                ParseError::UnexpectedSeparator(Token::Colon, Position { line: 2, column: 8 }),
+                ParseError::UnexpectedToken(
+                    Token::Number("1".to_string()),
+                    Position { line: 4, column: 7 }
+                ),
+            ],
+            errors
+        );
+        assert_eq!(
+            r#"{"b":true,"d":[1,2]}"#,
+            node.expect("不正なmemberを除いたobjectを返します。")
+                .to_json_string()
+        );
+
+        // lexerのエラーは不正な文字を読み飛ばす
+        let (node, errors) = parse_recovering("[1, @]");
+        assert_eq!(
+            vec![ParseError::Lexer(LexerError::UnexpectedChar(
+                '@',
+                Location(4, 4)
+            ))],
+            errors
+        );
+        assert_eq!("[1]", node.unwrap().to_json_string());
+
+        // 正しい入力ではエラーを返さない
+        let (node, errors) = parse_recovering("[1, 2]");
+        assert!(errors.is_empty());
+        assert_eq!(parse("[1, 2]").ok(), node);
+        // 既定のparseは最初のエラーで止まる
+        assert_eq!(
+            Err(ParseError::UnexpectedSeparator(
+                Token::Colon,
+                Position { line: 2, column: 8 }
+            )),
+            parse(input)
+        );
+    }
+
+    #[test]
+    fn format_should_keep_comments_and_relayout() {
+        let input = r#"// app config
+{"name":"demo",   // inline
+  "ports":[80,443,], /* block */ "nested":{ "a" : /* c */ 1 ,
+"b":[ ]},
+    // before end
+"z": 1.50}"#;
+        let output = format(input, FormatOptions::new()).unwrap();
+        assert_eq!(
+            r#"// app config
+{
+  "name": "demo", // inline
+  "ports": [
+    80,
+    443
+  ],
+  /* block */ "nested": {
+    "a": /* c */ 1,
+    "b": []
+  },
+  // before end
+  "z": 1.50
+}
+"#,
+            output
+        );
+        assert_eq!(Ok(output.clone()), format(&output, FormatOptions::new()));
+        assert_eq!(parse(input), parse(&output));
+        assert_eq!(
+            "[\n    1,\n    {\n        \"a\": 2,\n    },\n]\n",
+            format(
+                "[1, {\"a\": 2}]",
+                FormatOptions::new().indent(4).trailing_commas(true)
+            )
+            .unwrap()
+        );
+        assert!(format("[1,", FormatOptions::new()).is_err());
+    }
+
+    #[test]
+    fn format_should_insert_missing_comma_before_line_comment() {
+        let output = format("[1 // c\n 2]", FormatOptions::new()).unwrap();
+        assert_eq!("[\n  1, // c\n  2\n]\n", output);
+        let strict = ParseOptions::new().require_commas(true);
+        assert!(parse_with_options(&output, strict).is_ok());
+    }
+
+    #[test]
+    fn to_json_string_should_accept_top_level_number() {
+        for input in ["1", "-0", "1e+5", "[0e+1]"] {
+            assert_eq!(Ok(input.to_string()), to_json_string(input.to_string()));
+        }
+        for input in ["[01]", "[1.]", "[1e]", "[1.2.3]", "[1-2]", "[\"a\tb\"]"] {
+            assert!(to_json_string(input.to_string()).is_err(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn error_position_should_count_line_breaks() {
+        for (input, line, column) in [
+            ("@", 1, 1),
+            ("[\r\n  @]", 2, 3),
+            ("\n\r@", 3, 1),
+            ("", 1, 1),
+        ] {
+            let error = parse(input).expect_err(input);
+            assert_eq!(
+                Position { line, column },
+                error_position(input, &error),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn tokenize_should_return_kinds_and_raw_values() {
+        let tokens = tokenize("{\"あ\": 1, // c\n}").expect("tokenizeできます。");
+        let actual: Vec<(&str, &str, usize, usize)> = tokens
+            .iter()
+            .map(|token| (token.kind, token.value.as_str(), token.start, token.end))
+            .collect();
+        assert_eq!(
+            vec![
+                ("openBrace", "{", 0, 1),
+                ("string", "\"あ\"", 1, 4),
+                ("colon", ":", 4, 5),
+                ("whitespace", " ", 5, 6),
+                ("number", "1", 6, 7),
+                ("comma", ",", 7, 8),
+                ("whitespace", " ", 8, 9),
+                ("lineComment", "// c", 9, 13),
+                ("lineBreak", "\n", 13, 14),
+                ("closeBrace", "}", 14, 15),
+            ],
+            actual
+        );
+        assert!(tokenize("[@]").is_err());
+    }
+
+    #[test]
+    fn minify_should_strip_comments_and_trailing_commas() {
+        assert_eq!(
+            Ok(r#"{"a":1}"#.to_string()),
+            minify(r#"{ /* c */ "a": 1, }"#)
+        );
+        assert_eq!(
+            Ok(r#"{"z":[1e400,2],"a":{}}"#.to_string()),
+            minify("// head\n{\n  \"z\": [1e400 2,],\n  \"a\": {}\n}\n")
+        );
+        assert!(minify("[1,").is_err());
+    }
+
+    #[test]
+    fn comment_at_eof_should_be_accepted() {
+        assert_eq!(
+            Ok(r#"{"a":1}"#.to_string()),
+            to_json_string("{\"a\": 1} // trailing".to_string())
+        );
+        assert_eq!(
+            Ok("{\"a\": 1}".to_string()),
+            strip_comments("{\"a\": 1} // trailing")
+        );
+    }
+
+    #[test]
+    fn strip_comments_should_keep_other_whitespace() {
+        assert_eq!(
+            Ok("{\n  \"a\": 1\n}".to_string()),
+            strip_comments("{\n  \"a\": 1 // c\n}")
+        );
+        assert_eq!(
+            Ok("[\n\t1,   2,\n\t3\n]".to_string()),
+            strip_comments("[\n\t/* a */ 1,   2, /* b\n c */\n\t3\n  // d\n]")
+        );
+        assert!(strip_comments("[1,").is_err());
+    }
+
+    #[test]
+    fn parse_error_should_display_position() {
+        let err = parse("{\n  \"a\": 1,\n  \"b\" 2\n}").expect_err("`:`がないのでErrになります。");
+        assert_eq!(
+            "Unexpected token `Number(\"2\")` at line 3, column 7",
+            err.to_string()
+        );
+        let err = parse("{\n  \"a\"::1\n}").expect_err("`:`が重複しているのでErrになります。");
+        assert_eq!(
+            "Unexpected `:` where a value was expected at line 2, column 7",
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use crate::token::Location;
+use std::iter::{Enumerate, Peekable};
+use std::str::Chars;
+
+/// Lintのルール
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintRule {
+    /// `True`/`FALSE`/`NULL`など、小文字以外で書かれたリテラル
+    NonstandardLiteralCase,
+}
+
+impl LintRule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintRule::NonstandardLiteralCase => "nonstandard_literal_case",
+        }
+    }
+}
+
+/// Lintの違反情報
+/// `fix`は違反箇所(`location`)を置き換える文字列
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintViolation {
+    pub rule: LintRule,
+    pub message: String,
+    pub location: Location,
+    pub fix: Option<String>,
+}
+
+/// 入力を走査し、違反を見つけた順に返すiterator
+/// 文字列とコメントの中身は対象外
+pub struct Linter<'a> {
+    input: Peekable<Enumerate<Chars<'a>>>,
+}
+
+impl<'a> Linter<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Linter {
+            input: input.chars().enumerate().peekable(),
+        }
+    }
+
+    fn skip_string(&mut self) {
+        while let Some((_index, c)) = self.input.next() {
+            match c {
+                '"' => return,
+                '\\' => {
+                    self.input.next();
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        match self.input.peek() {
+            Some((_, '/')) => {
+                while self
+                    .input
+                    .next_if(|(_, c)| !matches!(c, '\n' | '\r'))
+                    .is_some()
+                {}
+            }
+            Some((_, '*')) => {
+                self.input.next();
+                let mut prev_asterisk = false;
+                for (_index, c) in self.input.by_ref() {
+                    if prev_asterisk && c == '/' {
+                        return;
+                    }
+                    prev_asterisk = c == '*';
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn scan_word(&mut self, first: char, index: usize) -> Option<LintViolation> {
+        let mut word = first.to_string();
+        let mut end = index;
+        while let Some((i, c)) = self.input.next_if(|(_, c)| c.is_alphanumeric()) {
+            word.push(c);
+            end = i;
+        }
+        let lower = word.to_lowercase();
+        if matches!(&lower as &str, "true" | "false" | "null") && word != lower {
+            Some(LintViolation {
+                rule: LintRule::NonstandardLiteralCase,
+                message: format!("`{}` should be written as `{}`", word, lower),
+                location: Location(index, end),
+                fix: Some(lower),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Iterator for Linter<'a> {
+    type Item = LintViolation;
+
+    fn next(&mut self) -> Option<LintViolation> {
+        while let Some((index, c)) = self.input.next() {
+            match c {
+                '"' => self.skip_string(),
+                '/' => self.skip_comment(),
+                c if c.is_alphabetic() => {
+                    if let Some(violation) = self.scan_word(c, index) {
+                        return Some(violation);
+                    }
+                }
+                _ => (),
+            }
+        }
+        None
+    }
+}
+
+/// すべての違反を収集する
+pub fn lint(input: &str) -> Vec<LintViolation> {
+    Linter::new(input).collect()
+}
+
+/// 最初の違反を見つけた時点で走査を止め、Errとして返す
+/// CIなどで違反の有無だけを判定したい場合に使う
+pub fn lint_fail_fast(input: &str) -> Result<(), LintViolation> {
+    match Linter::new(input).next() {
+        Some(violation) => Err(violation),
+        None => Ok(()),
+    }
+}
+
+/// autofixを適用した文字列を返す
+pub fn fix(input: &str) -> String {
+    let mut violations = Linter::new(input).filter(|v| v.fix.is_some()).peekable();
+    let mut result = String::with_capacity(input.len());
+    let mut skip_until = None;
+    for (index, c) in input.chars().enumerate() {
+        if let Some(end) = skip_until {
+            if index <= end {
+                continue;
+            }
+            skip_until = None;
+        }
+        match violations.next_if(|v| v.location.0 == index) {
+            Some(LintViolation {
+                location: Location(_, end),
+                fix: Some(fix),
+                ..
+            }) => {
+                result.push_str(&fix);
+                skip_until = Some(end);
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_should_report_nonstandard_literal_case() {
+        let violations = lint("[True, NULL]");
+        assert_eq!(2, violations.len(), "違反数が想定外です。");
+        assert_eq!(LintRule::NonstandardLiteralCase, violations[0].rule);
+        assert_eq!(Location(1, 4), violations[0].location);
+        assert_eq!(Some("true".to_string()), violations[0].fix);
+        assert_eq!(LintRule::NonstandardLiteralCase, violations[1].rule);
+        assert_eq!(Location(7, 10), violations[1].location);
+        assert_eq!(Some("null".to_string()), violations[1].fix);
+    }
+
+    #[test]
+    fn lint_should_ignore_strings_and_comments() {
+        let violations = lint(
+            r#"{
+    // True
+    /* FALSE */
+    "NULL": "True",
+    "a": true
+}"#,
+        );
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn lint_fail_fast_should_return_first_violation() {
+        let violation = lint_fail_fast("[True, NULL]").expect_err("違反があるのでErrになります。");
+        assert_eq!(Location(1, 4), violation.location);
+        assert_eq!(Some("true".to_string()), violation.fix);
+
+        // 2つ目以降は走査しない
+        let mut linter = Linter::new("[True, NULL]");
+        assert!(linter.next().is_some());
+        assert_eq!(Some((5, ',')), linter.input.next());
+
+        assert!(lint_fail_fast("[true, null]").is_ok());
+    }
+
+    #[test]
+    fn fix_should_normalize_literal_case() {
+        assert_eq!("[true, null]", fix("[True, NULL]"));
+        assert_eq!(
+            r#"{"True": false, "b": [null]}"#,
+            fix(r#"{"True": FaLsE, "b": [Null]}"#)
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! JSONCを読み込み、JSONに変換して標準出力に書き出すCLI
+//! `cargo run --features cli -- [OPTIONS] [FILE]`
+use jsonc_wasm::serializer::{SerializeOptions, Serializer};
+use jsonc_wasm::token::Position;
+use jsonc_wasm::{error_position, parse_with_comments, ParseError};
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: jsonc [OPTIONS] [FILE]
+
+Reads JSONC from FILE (or stdin when omitted) and writes it to stdout.
+Comments are kept unless --strip-comments is given.
+
+Options:
+  --pretty          Indent with 2 spaces
+  --indent N        Indent with N spaces
+  --minify          Write on a single line (default)
+  --strip-comments  Remove comments to produce plain JSON
+  -h, --help        Print this help";
+
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    file: Option<String>,
+    indent: Option<usize>,
+    strip_comments: bool,
+    help: bool,
+}
+
+/// 同じ種類のflagは後に指定したものを優先する
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut result = Args::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--pretty" => result.indent = Some(2),
+            "--indent" => {
+                let width = args.next().ok_or("`--indent` requires a number")?;
+                let width = width
+                    .parse()
+                    .map_err(|_| format!("invalid indent width `{}`", width))?;
+                result.indent = Some(width);
+            }
+            "--minify" => result.indent = None,
+            "--strip-comments" => result.strip_comments = true,
+            "-h" | "--help" => result.help = true,
+            flag if flag.starts_with('-') => return Err(format!("unknown option `{}`", flag)),
+            file if result.file.is_none() => result.file = Some(file.to_string()),
+            file => return Err(format!("unexpected argument `{}`", file)),
+        }
+    }
+    Ok(result)
+}
+
+fn read_input(file: Option<&str>) -> io::Result<String> {
+    match file {
+        Some(path) => std::fs::read_to_string(path),
+        None => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
+
+/// rustcのような形式でエラーを表示する
+fn render_error(name: &str, input: &str, error: &ParseError) -> String {
+    let Position { line, column } = error_position(input, error);
+    let source = input.lines().nth(line - 1).unwrap_or_default();
+    let gutter = " ".repeat(line.to_string().len());
+    format!(
+        "error: {error}\n{gutter}--> {name}:{line}:{column}\n{gutter} |\n{line} | {source}\n{gutter} | {marker}^",
+        marker = " ".repeat(column - 1),
+    )
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    if args.help {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let name = args.file.as_deref().unwrap_or("<stdin>");
+    let input = match read_input(args.file.as_deref()) {
+        Ok(input) => input,
+        Err(error) => {
+            eprintln!("error: failed to read `{}`: {}", name, error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (node, comments) = match parse_with_comments(&input) {
+        Ok(result) => result,
+        Err(error) => {
+            eprintln!("{}", render_error(name, &input, &error));
+            return ExitCode::FAILURE;
+        }
+    };
+    let options = match args.indent {
+        Some(width) => SerializeOptions::new().indent(width),
+        None => SerializeOptions::new(),
+    };
+    let output = if args.strip_comments {
+        Serializer::new(&options).serialize(&node)
+    } else {
+        Serializer::with_comments(&options, &comments).serialize(&node)
+    };
+    println!("{}", output);
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_args_should_read_flags() {
+        assert_eq!(
+            Ok(Args {
+                file: Some("a.jsonc".to_string()),
+                indent: Some(4),
+                strip_comments: true,
+                help: false,
+            }),
+            parse_args(args(&[
+                "--pretty",
+                "--strip-comments",
+                "a.jsonc",
+                "--indent",
+                "4"
+            ]))
+        );
+        assert_eq!(
+            None,
+            parse_args(args(&["--pretty", "--minify"])).unwrap().indent
+        );
+        assert!(parse_args(args(&["--indent", "x"])).is_err());
+        assert!(parse_args(args(&["--unknown"])).is_err());
+        assert!(parse_args(args(&["a.jsonc", "b.jsonc"])).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+use crate::token::{Spanned, Token};
+
+/// 空白とコメントを除いたtokenをJSONとして書き出す
+/// Nodeを経由しないため、keyの順序と数値の表記は入力のまま残る
+/// 入力はparserで検証済みであること
+pub(crate) fn write_tokens(tokens: impl IntoIterator<Item = Token>) -> String {
+    let mut tokens = tokens
+        .into_iter()
+        .filter(|token| !is_trivia(token))
+        .peekable();
+    let mut output = String::new();
+    let mut after_value = false;
+    while let Some(token) = tokens.next() {
+        // 末尾のcommaは出力しない
+        if token == Token::Comma
+            && matches!(tokens.peek(), Some(Token::CloseBrace | Token::CloseBracket))
+        {
+            continue;
+        }
+        // commaが省略された値の間にはcommaを補う
+        if after_value && starts_value(&token) {
+            output.push(',');
+        }
+        after_value = ends_value(&token);
+        match token {
+            Token::OpenBrace => output.push('{'),
+            Token::CloseBrace => output.push('}'),
+            Token::OpenBracket => output.push('['),
+            Token::CloseBracket => output.push(']'),
+            Token::StringValue(value) | Token::Identifier(value) => {
+                output.push('"');
+                output.push_str(&value);
+                output.push('"');
+            }
+            Token::Number(value) => output.push_str(&value),
+            Token::Boolean(value) => output.push_str(if value { "true" } else { "false" }),
+            Token::Null => output.push_str("null"),
+            Token::Comma => output.push(','),
+            Token::Colon => output.push(':'),
+            _ => (),
+        }
+    }
+    output
+}
+
+/// コメントと、コメントに付随する空白だけを除き、それ以外は入力(`chars`)のまま書き出す
+/// コメントの前の空白を除き、行頭のコメントはindentを残して後ろの空白を除く
+/// コメントだけの行は改行ごと除く
+pub(crate) fn strip_comments(tokens: &[Spanned<Token>], chars: &[char]) -> String {
+    let mut output = String::new();
+    for line in tokens.split_inclusive(|token| token.value == Token::BreakLine) {
+        if !line.iter().any(|token| is_comment(&token.value)) {
+            line.iter()
+                .for_each(|token| output.extend(raw(token, chars)));
+            continue;
+        }
+        let mut kept: Vec<&Spanned<Token>> = vec![];
+        let mut skip_spaces = false; // 行頭のコメントの直後の空白
+        for token in line {
+            match token.value {
+                Token::CommentLine(_) | Token::CommentBlock(_) => {
+                    skip_spaces = kept.iter().all(|token| is_space(&token.value));
+                    if !skip_spaces {
+                        while kept.last().is_some_and(|token| is_space(&token.value)) {
+                            kept.pop();
+                        }
+                    }
+                }
+                Token::WhiteSpaces(_) | Token::Tabs(_) if skip_spaces => (),
+                _ => {
+                    skip_spaces = false;
+                    kept.push(token);
+                }
+            }
+        }
+        if kept
+            .iter()
+            .all(|token| is_space(&token.value) || token.value == Token::BreakLine)
+        {
+            continue;
+        }
+        kept.iter()
+            .for_each(|token| output.extend(raw(token, chars)));
+    }
+    output
+}
+
+fn raw<'a>(token: &Spanned<Token>, chars: &'a [char]) -> &'a [char] {
+    &chars[token.location.0..=token.location.1]
+}
+
+fn is_comment(token: &Token) -> bool {
+    matches!(token, Token::CommentLine(_) | Token::CommentBlock(_))
+}
+
+fn is_space(token: &Token) -> bool {
+    matches!(token, Token::WhiteSpaces(_) | Token::Tabs(_))
+}
+
+pub(crate) fn is_trivia(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::BreakLine
+            | Token::WhiteSpaces(_)
+            | Token::Tabs(_)
+            | Token::CommentLine(_)
+            | Token::CommentBlock(_)
+    )
+}
+
+pub(crate) fn starts_value(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::OpenBrace
+            | Token::OpenBracket
+            | Token::StringValue(_)
+            | Token::Identifier(_)
+            | Token::Number(_)
+            | Token::Boolean(_)
+            | Token::Null
+    )
+}
+
+pub(crate) fn ends_value(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::CloseBrace
+            | Token::CloseBracket
+            | Token::StringValue(_)
+            | Token::Number(_)
+            | Token::Boolean(_)
+            | Token::Null
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_tokens_should_drop_trivia_and_trailing_commas() {
+        let tokens = vec![
+            Token::OpenBracket,
+            Token::WhiteSpaces(1),
+            Token::Number("1.50".to_string()),
+            Token::CommentBlock(" c ".to_string()),
+            Token::Number("2".to_string()),
+            Token::Comma,
+            Token::BreakLine,
+            Token::CloseBracket,
+        ];
+        assert_eq!("[1.50,2]", write_tokens(tokens));
+    }
+}
@@ -1,6 +1,36 @@
+use crate::comment::Comments;
+use crate::escape::{escape_string, unescape_string};
+use crate::path::{escape_pointer_segment, unescape_pointer_segment};
+use crate::serializer::{SerializeOptions, Serializer};
+use crate::utils::{canonical_number, is_non_finite_number};
 use std::collections::BTreeMap;
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+/// objectのkeyの有無と値
+/// PATCHのように「nullを指定した」と「指定していない」を区別するために使う
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Presence<'a> {
+    Absent,
+    Null,
+    Value(&'a Node),
+}
+
+/// `Node::stats`で集計した値の数と深さ
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NodeStats {
+    pub max_depth: usize, // 経路上のobject/arrayの数の最大値、`max_depth`と同じ数え方
+    pub nodes: usize,     // rootを含むすべての値の数
+    pub objects: usize,
+    pub arrays: usize,
+    pub strings: usize,
+    pub numbers: usize,
+    pub booleans: usize,
+    pub nulls: usize,
+    pub members: usize,  // すべてのobjectのmemberの合計
+    pub elements: usize, // すべてのarrayの要素の合計
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Node {
     StringValue(String),
     Number(String), // 浮動少数誤差を扱わないため、String
@@ -11,36 +41,413 @@ pub enum Node {
 }
 
 impl Node {
+    /// 文字列のNode、JSONの文字列としてescapeして保持する
+    /// ```
+    /// # use jsonc_wasm::Node;
+    /// let node = Node::object([("name", Node::string("a\"b")), ("tags", Node::array([1.into()]))]);
+    /// assert_eq!(r#"{"name":"a\"b","tags":[1]}"#, node.to_json_string());
+    /// ```
+    pub fn string(value: impl Into<String>) -> Self {
+        Node::StringValue(escape_string(&value.into()))
+    }
+
+    /// 数値のNode、JSONで表現できない`NaN`/`Infinity`は`Node::Null`
+    /// 整数を誤差なく保持する場合は`Node::from(i64)`を使う
+    pub fn number(value: f64) -> Self {
+        if value.is_finite() {
+            Node::Number(value.to_string())
+        } else {
+            Node::Null
+        }
+    }
+
+    pub fn bool(value: bool) -> Self {
+        Node::Boolean(value)
+    }
+
+    pub fn array(items: impl IntoIterator<Item = Node>) -> Self {
+        Node::Array(items.into_iter().collect())
+    }
+
+    /// `(key, 値)`の組からobjectを作る、keyはescapeして保持する
+    /// keyが重複する場合は後の値を使う
+    pub fn object<K: Into<String>>(members: impl IntoIterator<Item = (K, Node)>) -> Self {
+        Node::Object(
+            members
+                .into_iter()
+                .map(|(key, value)| (escape_string(&key.into()), value))
+                .collect(),
+        )
+    }
+
     pub fn to_json_string(&self) -> String {
+        self.to_json_string_with(&SerializeOptions::default())
+    }
+
+    /// `to_json_string`と同じ内容を、Stringを作らずに`w`へ書き込む
+    pub fn write_json(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        Serializer::new(&SerializeOptions::default()).write(self, w)
+    }
+
+    pub fn to_json_string_with(&self, options: &SerializeOptions) -> String {
+        Serializer::new(options).serialize(self)
+    }
+
+    /// `comments`をコメントとして埋め込んだJSONC
+    pub fn to_jsonc_string(&self, comments: &Comments) -> String {
+        Serializer::with_comments(&SerializeOptions::default(), comments).serialize(self)
+    }
+
+    /// `1.5`や範囲外の値は`None`
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Node::Number(value) => value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// 指数表記(`1e2`)も扱う
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Node::Number(value) => value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// 小数部と指数部のない数値か、数値以外は`false`
+    /// `Infinity`/`NaN`は整数として扱わない
+    pub fn is_integer(&self) -> bool {
+        self.as_integer_str().is_some()
+    }
+
+    /// 整数(`20`、`-3`)の数値を、入力の表記のまま返す
+    pub fn as_integer_str(&self) -> Option<&str> {
+        match self {
+            Node::Number(value)
+                if !value.contains(['.', 'e', 'E']) && !is_non_finite_number(value) =>
+            {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// 整数以外(`20.0`、`2e3`、`NaN`)の数値を、入力の表記のまま返す
+    pub fn as_float_str(&self) -> Option<&str> {
+        match self {
+            Node::Number(value) if !self.is_integer() => Some(value),
+            _ => None,
+        }
+    }
+
+    /// JSONのescapeを残した状態の文字列
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Node::StringValue(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Node::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Node>> {
+        match self {
+            Node::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&BTreeMap<String, Node>> {
+        match self {
+            Node::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// objectのkeyと値を、keyの順(辞書順)に返す
+    /// keyはescapeされた状態のまま、object以外は`None`
+    pub fn iter_object(&self) -> Option<impl Iterator<Item = (&str, &Node)>> {
+        self.as_object()
+            .map(|members| members.iter().map(|(key, value)| (key.as_str(), value)))
+    }
+
+    /// arrayの要素を順に返す、array以外は`None`
+    pub fn iter_array(&self) -> Option<impl Iterator<Item = &Node>> {
+        self.as_array().map(|items| items.iter())
+    }
+
+    /// objectの`key`が存在しない、`null`、それ以外の値のいずれか
+    /// object以外は`Presence::Absent`
+    pub fn get_presence(&self, key: &str) -> Presence<'_> {
+        match self.as_object().and_then(|members| members.get(key)) {
+            None => Presence::Absent,
+            Some(Node::Null) => Presence::Null,
+            Some(value) => Presence::Value(value),
+        }
+    }
+
+    /// JSON Pointer(RFC 6901)で指定した値を返す
+    /// `""`はroot自身で、存在しないkeyや範囲外のindexは`None`
+    /// keyはJSONのescapeを残した状態で比較する
+    pub fn pointer(&self, path: &str) -> Option<&Node> {
+        if path.is_empty() {
+            return Some(self);
+        }
+        path.strip_prefix('/')?
+            .split('/')
+            .try_fold(self, |node, segment| match node {
+                Node::Object(members) => members.get(&unescape_pointer_segment(segment)),
+                Node::Array(items) => {
+                    // `01`のような0始まりのindexは許容しない
+                    if segment.len() > 1 && segment.starts_with('0') {
+                        return None;
+                    }
+                    items.get(segment.parse::<usize>().ok()?)
+                }
+                _ => None,
+            })
+    }
+
+    /// `{"id1": {...}}`を`[{"id": "id1", ...}]`に変換する
+    /// objectのkeyは各要素の`id_key`に移す(既存の値は上書き)
+    /// object以外、またはobjectでない値を含む場合は`None`
+    pub fn object_to_array(&self, id_key: &str) -> Option<Node> {
+        let members = self.as_object()?;
+        let items = members
+            .iter()
+            .map(|(key, value)| {
+                let mut item = value.as_object()?.clone();
+                item.insert(id_key.to_string(), Node::StringValue(key.clone()));
+                Some(Node::Object(item))
+            })
+            .collect::<Option<Vec<Node>>>()?;
+        Some(Node::Array(items))
+    }
+
+    /// `[{"id": "id1", ...}]`を`{"id1": {...}}`に変換する
+    /// 各要素の`id_key`(文字列または数値)をkeyとして取り出し、重複した場合は後の要素を優先する
+    /// array以外、または`id_key`を持たない要素を含む場合は`None`
+    pub fn array_to_object(&self, id_key: &str) -> Option<Node> {
+        let items = self.as_array()?;
+        let members = items
+            .iter()
+            .map(|item| {
+                let mut member = item.as_object()?.clone();
+                let key = match member.remove(id_key)? {
+                    Node::StringValue(key) | Node::Number(key) => key,
+                    _ => return None,
+                };
+                Some((key, Node::Object(member)))
+            })
+            .collect::<Option<BTreeMap<String, Node>>>()?;
+        Some(Node::Object(members))
+    }
+
+    /// 最も深い葉へのJSON Pointerと、その深さを返す
+    /// 深さは経路上のobject/arrayの数で、root以外の値は0
+    pub fn deepest_path(&self) -> (String, usize) {
+        let children: Vec<(String, &Node)> = match self {
+            Node::Object(members) => members
+                .iter()
+                .map(|(key, value)| (escape_pointer_segment(key), value))
+                .collect(),
+            Node::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| (index.to_string(), item))
+                .collect(),
+            _ => return (String::new(), 0),
+        };
+        let mut deepest: Option<(String, usize)> = None;
+        for (segment, child) in children {
+            let (path, depth) = child.deepest_path();
+            if deepest.as_ref().is_none_or(|(_, max)| depth + 1 > *max) {
+                deepest = Some((format!("/{}{}", segment, path), depth + 1));
+            }
+        }
+        // 空のobject/arrayは自身が葉
+        deepest.unwrap_or((String::new(), 1))
+    }
+
+    /// 表記の違いを無視して比較する
+    /// 数値は値(`1`と`1.0`は等しい)、文字列とkeyはescapeを解除した文字列で比較する
+    /// derivedの`PartialEq`は入力の表記のまま比較する
+    pub fn semantically_eq(&self, other: &Node) -> bool {
+        match (self, other) {
+            (Node::Number(a), Node::Number(b)) => {
+                match (canonical_number(a), canonical_number(b)) {
+                    (Some(a), Some(b)) => a == b,
+                    _ => a == b,
+                }
+            }
+            (Node::StringValue(a), Node::StringValue(b)) => {
+                unescape_string(a) == unescape_string(b)
+            }
+            (Node::Array(a), Node::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.semantically_eq(b))
+            }
+            (Node::Object(a), Node::Object(b)) => {
+                let (a, b) = (unescape_keys(a), unescape_keys(b));
+                a.len() == b.len()
+                    && a.iter().all(|(key, value)| {
+                        b.get(key).is_some_and(|other| value.semantically_eq(other))
+                    })
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// 深さと、種類ごとの値の数を集計する
+    /// parse後に大きすぎる入力を弾くために使う
+    pub fn stats(&self) -> NodeStats {
+        let mut stats = NodeStats::default();
+        self.collect_stats(1, &mut stats);
+        stats
+    }
+
+    /// `depth`はこの値がobject/arrayの場合の深さ
+    fn collect_stats(&self, depth: usize, stats: &mut NodeStats) {
+        stats.nodes += 1;
         match self {
-            Node::StringValue(value) => format!(r#""{}""#, value).to_string(),
-            Node::Number(value) => value.clone(),
-            Node::Boolean(value) => {
-                if *value {
-                    "true".to_string()
-                } else {
-                    "false".to_string()
+            Node::StringValue(_) => stats.strings += 1,
+            Node::Number(_) => stats.numbers += 1,
+            Node::Boolean(_) => stats.booleans += 1,
+            Node::Null => stats.nulls += 1,
+            Node::Object(members) => {
+                stats.objects += 1;
+                stats.members += members.len();
+                stats.max_depth = stats.max_depth.max(depth);
+                for value in members.values() {
+                    value.collect_stats(depth + 1, stats);
                 }
             }
-            Node::Null => "null".to_string(),
             Node::Array(items) => {
-                let values: Vec<String> = items.iter().map(|item| item.to_json_string()).collect();
-                format!("[{}]", values.join(",")).to_string()
+                stats.arrays += 1;
+                stats.elements += items.len();
+                stats.max_depth = stats.max_depth.max(depth);
+                for item in items {
+                    item.collect_stats(depth + 1, stats);
+                }
             }
+        }
+    }
+
+    /// 値が`""`の文字列すべてのJSON Pointer
+    pub fn empty_string_paths(&self) -> Vec<String> {
+        let mut paths = vec![];
+        self.collect_paths(
+            String::new(),
+            &|node| matches!(node, Node::StringValue(value) if value.is_empty()),
+            &mut paths,
+        );
+        paths
+    }
+
+    /// JSONでは表現できない`Infinity`/`-Infinity`/`NaN`すべてのJSON Pointer
+    pub fn non_finite_number_paths(&self) -> Vec<String> {
+        let mut paths = vec![];
+        self.collect_paths(
+            String::new(),
+            &|node| matches!(node, Node::Number(value) if is_non_finite_number(value)),
+            &mut paths,
+        );
+        paths
+    }
+
+    fn collect_paths(
+        &self,
+        path: String,
+        matches: &dyn Fn(&Node) -> bool,
+        paths: &mut Vec<String>,
+    ) {
+        match self {
+            node if matches(node) => paths.push(path),
             Node::Object(members) => {
-                let mut key_values = vec![];
-                for (key, value) in members.iter() {
-                    key_values.push(format!(r#""{}":{}"#, key, value.to_json_string()));
+                for (key, value) in members {
+                    let path = format!("{}/{}", path, escape_pointer_segment(key));
+                    value.collect_paths(path, matches, paths);
                 }
-                format!("{{{}}}", key_values.join(",")).to_string()
             }
+            Node::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    item.collect_paths(format!("{}/{}", path, index), matches, paths);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// arrayを最大`page_size`件ずつの`Node::Array`に分割する
+    /// `page_size`が0の場合は1件ずつ分割し、array以外は`None`
+    pub fn paginate_array(&self, page_size: usize) -> Option<Vec<Node>> {
+        match self {
+            Node::Array(items) => Some(
+                items
+                    .chunks(page_size.max(1))
+                    .map(|chunk| Node::Array(chunk.to_vec()))
+                    .collect(),
+            ),
+            _ => None,
         }
     }
 }
 
+fn unescape_keys(members: &BTreeMap<String, Node>) -> BTreeMap<String, &Node> {
+    members
+        .iter()
+        .map(|(key, value)| (unescape_string(key), value))
+        .collect()
+}
+
+impl From<&str> for Node {
+    fn from(value: &str) -> Self {
+        Node::string(value)
+    }
+}
+
+impl From<String> for Node {
+    fn from(value: String) -> Self {
+        Node::string(value)
+    }
+}
+
+impl From<i64> for Node {
+    fn from(value: i64) -> Self {
+        Node::Number(value.to_string())
+    }
+}
+
+impl From<f64> for Node {
+    fn from(value: f64) -> Self {
+        Node::number(value)
+    }
+}
+
+impl From<bool> for Node {
+    fn from(value: bool) -> Self {
+        Node::Boolean(value)
+    }
+}
+
+/// `to_json_string`と同じJSONを出力する
+/// ```
+/// let node = jsonc_wasm::parse(r#"{"a": [1, null]}"#).unwrap();
+/// assert_eq!(r#"{"a":[1,null]}"#, format!("{}", node));
+/// ```
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_json(f)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::Node;
+    use super::{Node, NodeStats, Presence};
     use std::collections::BTreeMap;
 
     #[test]
@@ -55,6 +462,28 @@ mod test {
         assert_eq!(r#"999.99"#.to_string(), node.to_json_string());
     }
 
+    #[test]
+    fn number_node_should_distinguish_integer_and_float() {
+        for value in ["20", "-3", "12345678901234567890123"] {
+            let node = Node::Number(value.to_string());
+            assert!(node.is_integer(), "{}", value);
+            assert_eq!(
+                (Some(value), None),
+                (node.as_integer_str(), node.as_float_str())
+            );
+        }
+        for value in ["20.0", "2e3", "-1E-2", "NaN"] {
+            let node = Node::Number(value.to_string());
+            assert!(!node.is_integer(), "{}", value);
+            assert_eq!(
+                (None, Some(value)),
+                (node.as_integer_str(), node.as_float_str())
+            );
+        }
+        assert!(!Node::StringValue("20".to_string()).is_integer());
+        assert_eq!(None, Node::Null.as_float_str());
+    }
+
     #[test]
     fn bool_node_should_be_value() {
         let node = Node::Boolean(false);
@@ -71,28 +500,335 @@ mod test {
 
     #[test]
     fn object_node_to_string() {
+        let node = Node::object([("key", "value".into())]);
+        assert_eq!(r#"{"key":"value"}"#.to_string(), node.to_json_string());
+        let node = Node::object([
+            ("a", Node::Null),
+            ("b", Node::number(999.99)),
+            ("c", true.into()),
+        ]);
+        assert_eq!(
+            r#"{"a":null,"b":999.99,"c":true}"#.to_string(),
+            node.to_json_string()
+        );
+        let node = Node::object([("a", Node::array([111.into(), 222.into()]))]);
+        assert_eq!(r#"{"a":[111,222]}"#.to_string(), node.to_json_string());
+    }
+
+    #[test]
+    fn constructors_should_escape_and_reject_non_finite() {
+        assert_eq!(
+            Node::StringValue(r#"a\"b\n"#.to_string()),
+            Node::string("a\"b\n")
+        );
+        assert_eq!(
+            Node::Object(BTreeMap::from([(r#"k\\"#.to_string(), Node::Null)])),
+            Node::object([("k\\", Node::Null)])
+        );
+        assert_eq!(
+            Node::Number("9007199254740993".to_string()),
+            Node::from(9007199254740993i64)
+        );
+        assert_eq!(Node::Number("0.5".to_string()), Node::from(0.5));
+        assert_eq!(Node::Null, Node::number(f64::NAN));
+        assert_eq!(Node::Boolean(false), Node::bool(false));
+    }
+
+    #[test]
+    fn number_node_accessors() {
+        let node = Node::Number("20".to_string());
+        assert_eq!(Some(20), node.as_i64());
+        assert_eq!(Some(20.0), node.as_f64());
+        let node = Node::Number("1.5".to_string());
+        assert_eq!(None, node.as_i64());
+        assert_eq!(Some(1.5), node.as_f64());
+        let node = Node::Number("-2.5E-3".to_string());
+        assert_eq!(Some(-0.0025), node.as_f64());
+        let node = Node::Number("9223372036854775808".to_string());
+        assert_eq!(None, node.as_i64());
+        assert_eq!(None, Node::StringValue("20".to_string()).as_i64());
+        assert_eq!(None, Node::Null.as_f64());
+    }
+
+    #[test]
+    fn node_accessors() {
+        assert_eq!(Some("test"), Node::StringValue("test".to_string()).as_str());
+        assert_eq!(Some(true), Node::Boolean(true).as_bool());
+        assert_eq!(None, Node::Null.as_str());
+        assert_eq!(None, Node::Null.as_bool());
+
+        let node = Node::Array(vec![Node::Null]);
+        assert_eq!(Some(&vec![Node::Null]), node.as_array());
+        assert_eq!(None, node.as_object());
+        let node = Node::Object(BTreeMap::from([("a".to_string(), Node::Null)]));
+        assert_eq!(
+            Some(&Node::Null),
+            node.as_object().and_then(|members| members.get("a"))
+        );
+        assert_eq!(None, node.as_array());
+    }
+
+    #[test]
+    fn object_and_array_collection_round_trip() {
+        let object = Node::Object(BTreeMap::from([
+            (
+                "id1".to_string(),
+                Node::Object(BTreeMap::from([(
+                    "name".to_string(),
+                    Node::StringValue("sato".to_string()),
+                )])),
+            ),
+            (
+                "id2".to_string(),
+                Node::Object(BTreeMap::from([(
+                    "name".to_string(),
+                    Node::StringValue("suzuki".to_string()),
+                )])),
+            ),
+        ]));
+        let array = object
+            .object_to_array("id")
+            .expect("objectのobjectは変換できます。");
+        assert_eq!(
+            r#"[{"id":"id1","name":"sato"},{"id":"id2","name":"suzuki"}]"#,
+            array.to_json_string()
+        );
+        assert_eq!(Some(object), array.array_to_object("id"));
+    }
+
+    #[test]
+    fn collection_conversion_should_fail_with_unexpected_shape() {
+        let object = Node::Object(BTreeMap::from([("id1".to_string(), Node::Null)]));
+        assert_eq!(None, object.object_to_array("id"));
+        let array = Node::Array(vec![Node::Object(BTreeMap::from([(
+            "name".to_string(),
+            Node::Null,
+        )]))]);
+        assert_eq!(None, array.array_to_object("id"));
+        assert_eq!(None, Node::Null.object_to_array("id"));
+        assert_eq!(None, Node::Null.array_to_object("id"));
+    }
+
+    #[test]
+    fn deepest_path_should_point_into_deepest_branch() {
+        let node = Node::Object(BTreeMap::from([
+            ("a".to_string(), Node::Number("1".to_string())),
+            (
+                "b".to_string(),
+                Node::Object(BTreeMap::from([(
+                    "c".to_string(),
+                    Node::Array(vec![
+                        Node::Number("1".to_string()),
+                        Node::Object(BTreeMap::from([("d".to_string(), Node::Boolean(true))])),
+                    ]),
+                )])),
+            ),
+            (
+                "e".to_string(),
+                Node::Object(BTreeMap::from([("f/g".to_string(), Node::Array(vec![]))])),
+            ),
+        ]));
+        assert_eq!(("/b/c/1/d".to_string(), 4), node.deepest_path());
+
         let node = Node::Object(BTreeMap::from([(
-            "key".to_string(),
-            Node::StringValue("value".to_string()),
+            "f/g".to_string(),
+            Node::Array(vec![Node::Array(vec![])]),
         )]));
-        assert_eq!(r#"{"key":"value"}"#.to_string(), node.to_json_string());
+        assert_eq!(("/f~1g/0".to_string(), 3), node.deepest_path());
+
+        assert_eq!((String::new(), 0), Node::Null.deepest_path());
+        assert_eq!((String::new(), 1), Node::Array(vec![]).deepest_path());
+    }
+
+    #[test]
+    fn get_presence_should_distinguish_absent_and_null() {
+        let node = Node::Object(BTreeMap::from([("a".to_string(), Node::Null)]));
+        assert_eq!(Presence::Null, node.get_presence("a"));
+        assert_eq!(Presence::Absent, node.get_presence("b"));
+        assert_eq!(
+            Presence::Absent,
+            Node::Object(BTreeMap::new()).get_presence("a")
+        );
+
+        let node = Node::Object(BTreeMap::from([("a".to_string(), Node::Boolean(false))]));
+        assert_eq!(
+            Presence::Value(&Node::Boolean(false)),
+            node.get_presence("a")
+        );
+        assert_eq!(Presence::Absent, Node::Null.get_presence("a"));
+    }
+
+    #[test]
+    fn pointer_should_find_nested_value() {
         let node = Node::Object(BTreeMap::from([
-            ("a".to_string(), Node::Null),
-            ("b".to_string(), Node::Number("999.99".to_string())),
-            ("c".to_string(), Node::Boolean(true)),
+            (
+                "user".to_string(),
+                Node::Object(BTreeMap::from([(
+                    "addresses".to_string(),
+                    Node::Array(vec![Node::Object(BTreeMap::from([(
+                        "city".to_string(),
+                        Node::StringValue("tokyo".to_string()),
+                    )]))]),
+                )])),
+            ),
+            ("a/b".to_string(), Node::Number("1".to_string())),
+            ("m~n".to_string(), Node::Boolean(true)),
+            ("".to_string(), Node::Null),
         ]));
+        assert_eq!(Some(&node), node.pointer(""));
         assert_eq!(
-            r#"{"a":null,"b":999.99,"c":true}"#.to_string(),
-            node.to_json_string()
+            Some(&Node::StringValue("tokyo".to_string())),
+            node.pointer("/user/addresses/0/city")
+        );
+        assert_eq!(Some(&Node::Number("1".to_string())), node.pointer("/a~1b"));
+        assert_eq!(Some(&Node::Boolean(true)), node.pointer("/m~0n"));
+        assert_eq!(Some(&Node::Null), node.pointer("/"));
+    }
+
+    #[test]
+    fn pointer_should_return_none_for_missing_value() {
+        let node = Node::Object(BTreeMap::from([(
+            "items".to_string(),
+            Node::Array(vec![Node::Null]),
+        )]));
+        assert_eq!(None, node.pointer("/missing"));
+        assert_eq!(None, node.pointer("/items/1"));
+        assert_eq!(None, node.pointer("/items/00"));
+        assert_eq!(None, node.pointer("/items/-"));
+        assert_eq!(None, node.pointer("/items/0/a"));
+        assert_eq!(None, node.pointer("items"));
+    }
+
+    #[test]
+    fn empty_string_paths_should_report_empty_values() {
+        let node = Node::Object(BTreeMap::from([
+            ("a".to_string(), Node::StringValue("".to_string())),
+            (
+                "b".to_string(),
+                Node::Object(BTreeMap::from([(
+                    "c".to_string(),
+                    Node::StringValue("".to_string()),
+                )])),
+            ),
+            ("d".to_string(), Node::StringValue("x".to_string())),
+            (
+                "e".to_string(),
+                Node::Array(vec![Node::Null, Node::StringValue("".to_string())]),
+            ),
+        ]));
+        assert_eq!(vec!["/a", "/b/c", "/e/1"], node.empty_string_paths());
+        assert_eq!(
+            vec![""],
+            Node::StringValue("".to_string()).empty_string_paths()
+        );
+        assert!(Node::Null.empty_string_paths().is_empty());
+    }
+
+    #[test]
+    fn iter_object_and_iter_array_should_walk_children() {
+        let node =
+            crate::parse(r#"{"b": [1, "x", null], "a": {"c": true}}"#).expect("parseできます。");
+        let keys: Vec<&str> = node.iter_object().unwrap().map(|(key, _)| key).collect();
+        assert_eq!(vec!["a", "b"], keys);
+        let (_, items) = node.iter_object().unwrap().last().unwrap();
+        assert_eq!(
+            vec!["1", r#""x""#, "null"],
+            items
+                .iter_array()
+                .unwrap()
+                .map(Node::to_json_string)
+                .collect::<Vec<_>>()
+        );
+        assert!(items.iter_object().is_none());
+        assert!(node.iter_array().is_none());
+        assert!(Node::Null.iter_array().is_none());
+    }
+
+    #[test]
+    fn semantically_eq_should_ignore_notation() {
+        let a = crate::parse(r#"{"a":1,"b":2}"#).unwrap();
+        let b = crate::parse(r#"{"b":2,"a":1.0}"#).unwrap();
+        assert!(a.semantically_eq(&b));
+        assert_ne!(a, b);
+
+        let a = crate::parse(r#"{"\u0041": ["x", 1e2, null]}"#).unwrap();
+        let b = crate::parse(r#"{"A": ["\u0078", 100, null]}"#).unwrap();
+        assert!(a.semantically_eq(&b));
+        let c = crate::parse(r#"{"A": ["x", 100, false]}"#).unwrap();
+        assert!(!a.semantically_eq(&c));
+        let d = crate::parse(r#"{"A": ["x", 100]}"#).unwrap();
+        assert!(!a.semantically_eq(&d));
+    }
+
+    #[test]
+    fn stats_should_count_nodes_and_depth() {
+        let node = crate::parse(
+            r#"{"name": "sato", "tags": ["a", 1, true, null, {"deep": []}], "empty": {}}"#,
+        )
+        .expect("parseできます。");
+        assert_eq!(
+            NodeStats {
+                max_depth: 4,
+                nodes: 10,
+                objects: 3,
+                arrays: 2,
+                strings: 2,
+                numbers: 1,
+                booleans: 1,
+                nulls: 1,
+                members: 4,
+                elements: 5,
+            },
+            node.stats()
         );
+        assert_eq!(0, Node::Null.stats().max_depth);
+    }
+
+    #[test]
+    fn non_finite_number_paths_should_report_literals() {
+        let node = Node::Array(vec![
+            Node::Number("NaN".to_string()),
+            Node::Number("1".to_string()),
+            Node::Object(BTreeMap::from([(
+                "a".to_string(),
+                Node::Number("-Infinity".to_string()),
+            )])),
+        ]);
+        assert_eq!(vec!["/0", "/2/a"], node.non_finite_number_paths());
+    }
+
+    #[test]
+    fn write_json_should_match_to_json_string() {
         let node = Node::Object(BTreeMap::from([(
             "a".to_string(),
             Node::Array(vec![
-                Node::Number("111".to_string()),
-                Node::Number("222".to_string()),
+                Node::Boolean(true),
+                Node::StringValue("x".to_string()),
             ]),
         )]));
-        assert_eq!(r#"{"a":[111,222]}"#.to_string(), node.to_json_string());
+        let mut buffer = String::from("data=");
+        node.write_json(&mut buffer).unwrap();
+        assert_eq!(format!("data={}", node.to_json_string()), buffer);
+        assert_eq!(node.to_json_string(), node.to_string());
+    }
+
+    #[test]
+    fn paginate_array_should_split_into_chunks() {
+        let node = Node::Array((0..10).map(|i| Node::Number(i.to_string())).collect());
+        let pages = node.paginate_array(4).expect("arrayは分割できます。");
+        let lengths: Vec<usize> = pages
+            .iter()
+            .map(|page| match page {
+                Node::Array(items) => items.len(),
+                _ => panic!("pageはarrayです。"),
+            })
+            .collect();
+        assert_eq!(vec![4, 4, 2], lengths);
+        assert_eq!(r#"[8,9]"#, pages[2].to_json_string());
+        assert_eq!(r#"[0,1,2,3]"#, pages[0].to_json_string());
+
+        assert_eq!(Some(vec![]), Node::Array(vec![]).paginate_array(4));
+        assert_eq!(None, Node::Null.paginate_array(4));
     }
 
     #[test]
@@ -0,0 +1,155 @@
+/// object/arrayのネストの上限の既定値
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// lexer/parserの設定
+/// 既定値はコメントとtrailing commaを許容するJSON(現状のJSONC)
+/// ```
+/// # use jsonc_wasm::options::ParseOptions;
+/// let options = ParseOptions::new().allow_single_quotes(true).max_depth(64);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub(crate) allow_single_quotes: bool,
+    pub(crate) allow_unquoted_keys: bool,
+    pub(crate) allow_identifier_values: bool,
+    pub(crate) allow_number_keys: bool,
+    pub(crate) allow_hex_numbers: bool,
+    pub(crate) allow_non_finite_numbers: bool,
+    pub(crate) allow_json5_decimals: bool,
+    pub(crate) allow_hash_comments: bool,
+    pub(crate) require_commas: bool,
+    pub(crate) allow_comments: bool,
+    pub(crate) allow_trailing_commas: bool,
+    pub(crate) max_depth: usize,
+    pub(crate) max_input_len: Option<usize>,
+    pub(crate) max_string_len: Option<usize>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            allow_single_quotes: false,
+            allow_unquoted_keys: false,
+            allow_identifier_values: false,
+            allow_number_keys: false,
+            allow_hex_numbers: false,
+            allow_non_finite_numbers: false,
+            allow_json5_decimals: false,
+            allow_hash_comments: false,
+            require_commas: false,
+            allow_comments: true,
+            allow_trailing_commas: true,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_input_len: None,
+            max_string_len: None,
+        }
+    }
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// RFC 8259のJSONのみを受理する設定
+    /// コメント・末尾のcomma・commaの欠落を許容しない
+    pub fn strict() -> Self {
+        Self::default()
+            .require_commas(true)
+            .allow_comments(false)
+            .allow_trailing_commas(false)
+    }
+
+    /// JSON5の`'single quotes'`の文字列を許容する
+    pub fn allow_single_quotes(mut self, allow: bool) -> Self {
+        self.allow_single_quotes = allow;
+        self
+    }
+
+    /// JSON5の`{ name: "sato" }`のようなquoteされていないkeyを許容する
+    /// lexerは`true`/`false`/`null`以外の単語を`Token::Identifier`として読み取る
+    pub fn allow_unquoted_keys(mut self, allow: bool) -> Self {
+        self.allow_unquoted_keys = allow;
+        self
+    }
+
+    /// key以外の位置の`Token::Identifier`を文字列の値として許容する
+    pub fn allow_identifier_values(mut self, allow: bool) -> Self {
+        self.allow_identifier_values = allow;
+        self
+    }
+
+    /// lenient mode: `{1: "a"}`のようなNumberのkeyを許容し、文字列のkeyとして扱う
+    pub fn allow_number_keys(mut self, allow: bool) -> Self {
+        self.allow_number_keys = allow;
+        self
+    }
+
+    /// JSON5の`0xFF`のような16進数の整数を許容する
+    /// 出力がJSONとして有効になるよう、`Node::Number`には10進数で保持する
+    pub fn allow_hex_numbers(mut self, allow: bool) -> Self {
+        self.allow_hex_numbers = allow;
+        self
+    }
+
+    /// JSON5の`Infinity`/`-Infinity`/`NaN`を`Node::Number`として許容する
+    /// JSONでは表現できないため、`to_json_string_with_options`はErrを返す
+    pub fn allow_non_finite_numbers(mut self, allow: bool) -> Self {
+        self.allow_non_finite_numbers = allow;
+        self
+    }
+
+    /// JSON5の`.5`/`5.`/`+5`のような数値を許容し、`0.5`/`5`/`5`に正規化する
+    pub fn allow_json5_decimals(mut self, allow: bool) -> Self {
+        self.allow_json5_decimals = allow;
+        self
+    }
+
+    /// `# comment`を`//`と同じ行コメントとして扱う
+    /// JSONCとして出力する場合は`//`のコメントになる
+    pub fn allow_hash_comments(mut self, allow: bool) -> Self {
+        self.allow_hash_comments = allow;
+        self
+    }
+
+    /// strict mode: member/要素の間のcommaを必須にする
+    /// 既定では`{"a":1 "b":2}`のようなcommaの欠落を許容する
+    pub fn require_commas(mut self, require: bool) -> Self {
+        self.require_commas = require;
+        self
+    }
+
+    /// `false`の場合、コメントを`ParseError::CommentsNotAllowed`とする
+    /// lexerはコメントを読み取り、parserで拒否する
+    pub fn allow_comments(mut self, allow: bool) -> Self {
+        self.allow_comments = allow;
+        self
+    }
+
+    /// `false`の場合、`[1,]`のような末尾のcommaを`ParseError::TrailingCommaNotAllowed`とする
+    pub fn allow_trailing_commas(mut self, allow: bool) -> Self {
+        self.allow_trailing_commas = allow;
+        self
+    }
+
+    /// object/arrayのネストの上限、超えると`ParseError::MaxDepthExceeded`を返す
+    /// parse自体は再帰しないが、Nodeの破棄や出力は再帰するため上限を設けている
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// 入力のbyte数の上限、超えると読み取る前に`LexerError::InputTooLarge`を返す
+    /// 既定では上限なし
+    pub fn max_input_len(mut self, max_input_len: usize) -> Self {
+        self.max_input_len = Some(max_input_len);
+        self
+    }
+
+    /// 文字列(keyを含む)のquoteの間の文字数の上限、超えると`LexerError::StringTooLong`を返す
+    /// escapeは入力上の文字数で数える、既定では上限なし
+    pub fn max_string_len(mut self, max_string_len: usize) -> Self {
+        self.max_string_len = Some(max_string_len);
+        self
+    }
+}
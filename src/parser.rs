@@ -1,199 +1,553 @@
+use crate::comment::{Comment, Comments};
 use crate::node::Node;
-use crate::token::Token;
-use anyhow::{ensure, Result};
+use crate::options::ParseOptions;
+use crate::path::{JsonPath, PathSegment};
+use crate::token::{LexerError, Position, Spanned, Token};
 use std::collections::BTreeMap;
 use std::iter::Peekable;
-use std::slice::Iter;
+use std::vec::IntoIter;
 use thiserror::Error;
 
+type Result<T> = std::result::Result<T, ParseError>;
+
+#[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum ParseError {
     #[error("Not found token")]
     NotFoundToken,
-    #[error("Unexpected Token: `{0}`")]
-    UnexpectedToken(String),
-    #[error("Unexpected consumed up Token")]
-    UnexpectedConsumedUpToken,
-    #[error("Un closed Token")]
-    UnClosedToken,
+    #[error("Unexpected token `{0:?}` at {1}")]
+    UnexpectedToken(Token, Position),
+    #[error("Unexpected consumed up Token after {0}")]
+    UnexpectedConsumedUpToken(Position),
+    #[error("Unclosed {} opened at {1}", container_name(.0))]
+    UnClosedToken(Token, Position), // 閉じていない開き括弧とその位置
+    #[error("Exceeded max depth `{0}` at {1}")]
+    MaxDepthExceeded(usize, Position),
+    #[error("Missing comma at {0}")]
+    MissingComma(Position),
+    #[error("Unexpected `{}` where a value was expected at {1}", separator_char(.0))]
+    UnexpectedSeparator(Token, Position), // 値の位置の`:`と`,`
+    #[error("Comments are not allowed at {0}")]
+    CommentsNotAllowed(Position),
+    #[error("Trailing comma is not allowed at {0}")]
+    TrailingCommaNotAllowed(Position),
+    #[error("Unexpected closing bracket `{0:?}` without matching opening bracket at {1}")]
+    UnexpectedClosing(Token, Position),
+    #[error("Invalid UTF-8 sequence at byte {0}")]
+    InvalidUtf8(usize),
+    #[error(transparent)]
+    Lexer(#[from] LexerError),
+}
+
+impl ParseError {
+    /// エラーの位置
+    /// 入力が空の場合と、UTF-8とlexerのエラーは`None`
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            ParseError::UnexpectedToken(_, position)
+            | ParseError::UnexpectedConsumedUpToken(position)
+            | ParseError::UnClosedToken(_, position)
+            | ParseError::MaxDepthExceeded(_, position)
+            | ParseError::MissingComma(position)
+            | ParseError::UnexpectedClosing(_, position)
+            | ParseError::UnexpectedSeparator(_, position)
+            | ParseError::CommentsNotAllowed(position)
+            | ParseError::TrailingCommaNotAllowed(position) => Some(*position),
+            ParseError::NotFoundToken | ParseError::InvalidUtf8(_) | ParseError::Lexer(_) => None,
+        }
+    }
 }
 
-pub struct Parser<'a> {
-    tokens: Peekable<Iter<'a, Token>>,
+/// tokenはcloneせず、受け取ったiteratorからmoveして使う
+/// 借用したtokenをparseする場合は`tokens.iter().cloned()`を渡す
+pub struct Parser<I: Iterator<Item = Spanned<Token>> = IntoIter<Spanned<Token>>> {
+    tokens: Peekable<I>,
+    options: ParseOptions,
+    depth: usize,
+    last_position: Position, // 最後に読んだtokenの位置、入力が途中で終わった場合のエラーに使う
+    collector: Option<CommentCollector>,
+    errors: Option<Vec<ParseError>>, // 回復モードで記録したエラー
 }
 
-impl<'a> Parser<'a> {
-    pub fn new(tokens: &'a Vec<Token>) -> Self {
+/// コメントを保持してparseする場合の状態
+#[derive(Default)]
+struct CommentCollector {
+    path: JsonPath,
+    pending: Vec<Comment>, // まだnodeに割り当てていないコメント
+    comments: Comments,
+}
+
+impl CommentCollector {
+    fn attach_leading(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        self.comments.push_leading(self.path.to_pointer(), pending);
+    }
+
+    fn attach_trailing(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        self.comments.push_trailing(self.path.to_pointer(), pending);
+    }
+}
+
+impl<I: Iterator<Item = Spanned<Token>>> Parser<I> {
+    pub fn new(tokens: impl IntoIterator<IntoIter = I>) -> Self {
+        Self::with_options(tokens, ParseOptions::default())
+    }
+
+    pub fn with_options(tokens: impl IntoIterator<IntoIter = I>, options: ParseOptions) -> Self {
         Parser {
-            tokens: tokens.iter().peekable(),
+            tokens: tokens.into_iter().peekable(),
+            options,
+            depth: 0,
+            last_position: Position::default(),
+            collector: None,
+            errors: None,
         }
     }
 
     pub fn parse(&mut self) -> Result<Node> {
-        if self.tokens.len() == 0 {
-            return Err(ParseError::NotFoundToken.into());
+        if self.tokens.peek().is_none() {
+            return Err(ParseError::NotFoundToken);
         }
         let result = self.parse_value()?;
-        ensure!(
-            self.next_grammar().is_none(),
-            ParseError::UnexpectedToken("contains multiple values".to_string())
-        );
+        if let Some(token) = self.next_grammar()? {
+            // 複数の値は許容しない
+            return Err(unexpected_closing(token, None));
+        }
         Ok(result)
     }
 
+    /// 回復モード: エラーがあっても読み飛ばして続け、すべてのエラーを返す
+    /// object/arrayの中のエラーは同じ階層のcommaか閉じ括弧まで読み飛ばし、その要素を除いて続ける
+    /// rootの値自体が不正な場合はNodeを返さない
+    pub fn parse_recovering(&mut self) -> (Option<Node>, Vec<ParseError>) {
+        self.errors = Some(vec![]);
+        let result = self.parse();
+        let mut errors = self.errors.take().unwrap_or_default();
+        match result {
+            Ok(node) => (Some(node), errors),
+            Err(error) => {
+                errors.push(error);
+                (None, errors)
+            }
+        }
+    }
+
+    /// 連続した複数の値(NDJSONなど)を順に返す
+    /// 値の間の空白・改行・コメントは読み飛ばす
+    pub fn parse_many(&mut self) -> Result<Vec<Node>> {
+        let mut nodes = vec![];
+        while let Some(token) = self.next_grammar()? {
+            nodes.push(self.parse_token(token)?);
+        }
+        Ok(nodes)
+    }
+
+    /// コメントを読み飛ばさず、直後のnodeのJSON Pointerに対応づけて返す
+    pub fn parse_with_comments(&mut self) -> Result<(Node, Comments)> {
+        self.collector = Some(CommentCollector::default());
+        let node = self.parse()?;
+        let mut collector = self.collector.take().unwrap_or_default();
+        collector.comments.push_end(collector.pending);
+        Ok((node, collector.comments))
+    }
+
     fn parse_value(&mut self) -> Result<Node> {
         let token = self
-            .next_grammar()
-            .ok_or(ParseError::UnexpectedConsumedUpToken)?;
-        match token {
-            Token::StringValue(value) => Ok(Node::StringValue(value)),
-            Token::Number(value) => Ok(Node::Number(value)),
-            Token::Boolean(value) => Ok(Node::Boolean(value)),
-            Token::Null => Ok(Node::Null),
-            Token::OpenBrace => self.parse_object(),
-            Token::OpenBracket => self.parse_array(),
-            _ => Err(ParseError::UnexpectedToken(
-                "contains a token other than the value".to_string(),
-            )
-            .into()),
-        }
+            .next_grammar()?
+            .ok_or(ParseError::UnexpectedConsumedUpToken(self.last_position))?;
+        self.parse_token(token)
     }
 
-    fn parse_object(&mut self) -> Result<Node> {
-        let mut times = 0;
-        let mut member = BTreeMap::new();
+    /// 読み取り済みの値の最初のtokenからNodeを作る
+    /// ネストは再帰せず、読み取り中のobject/arrayをstackに積んで処理する
+    fn parse_token(&mut self, token: Spanned<Token>) -> Result<Node> {
+        let mut stack = vec![];
+        let mut next = Some(token); // 次に読み取る値の最初のtoken
+        let mut node = None; // 完成した値
+        let mut after_comma = false;
         loop {
-            // close,comma,stringのいづれか
-            let first_token = self.next_grammar().ok_or(ParseError::UnClosedToken)?;
-            let key = match first_token {
-                Token::CloseBrace => break, // ループを終了
-                Token::Comma => {
-                    // 0回目の時はcommaはなし
-                    if times == 0 {
-                        return Err(ParseError::UnexpectedToken(
-                            "first comma is not allowed".to_string(),
-                        )
-                        .into());
-                    } else {
-                        let token = self.next_grammar().ok_or(ParseError::UnexpectedToken(
-                            "found a Token that cannot be a key".to_string(),
-                        ))?;
-                        match token {
-                            Token::CloseBrace => break, // ループを終了
-                            Token::StringValue(key) => key,
-                            _ => {
-                                return Err(ParseError::UnexpectedToken(
-                                    "found a Token that cannot be a key".to_string(),
-                                )
-                                .into());
-                            }
+            let result = match next.take() {
+                Some(token) => self
+                    .start_value(token, &mut stack)
+                    .map(|value| node = value),
+                // 値が完成したら親に追加し、次の値の最初のtokenを探す
+                None => {
+                    let container = match (node.take(), stack.last_mut()) {
+                        (Some(value), None) => return Ok(value),
+                        (Some(value), Some(container)) => {
+                            container.push(value);
+                            self.leave_path();
+                            container
+                        }
+                        (None, Some(container)) => container,
+                        (None, None) => unreachable!("値が未完成の場合はobject/arrayの中"),
+                    };
+                    match self.next_element(container, std::mem::take(&mut after_comma)) {
+                        Ok(Some(token)) => {
+                            next = Some(token);
+                            Ok(())
                         }
+                        Ok(None) => {
+                            node = stack.pop().map(Container::into_node);
+                            self.depth -= 1;
+                            Ok(())
+                        }
+                        Err(error) => Err(error),
                     }
                 }
-                Token::StringValue(key) => key, // key tokenはstringのみ許容 https://www.rfc-editor.org/rfc/rfc8259#section-4
-                _ => {
-                    return Err(ParseError::UnexpectedToken(
-                        "found a Token that cannot be a key".to_string(),
-                    )
-                    .into());
-                }
             };
+            let Err(error) = result else {
+                continue;
+            };
+            match self.recover(error, stack.last())? {
+                Recovery::Resume => (),
+                Recovery::AfterComma => after_comma = true,
+                Recovery::Close => {
+                    node = stack.pop().map(Container::into_node);
+                    self.depth -= 1;
+                }
+                Recovery::End => {
+                    // 読み取り中のobject/arrayをすべて閉じる
+                    while let Some(mut container) = stack.pop() {
+                        if let Some(value) = node.take() {
+                            container.push(value);
+                        }
+                        node = Some(container.into_node());
+                    }
+                    return node.ok_or(ParseError::UnexpectedConsumedUpToken(self.last_position));
+                }
+            }
+        }
+    }
 
-            match (key, self.next_grammar(), self.parse_value()?) {
-                (key, Some(Token::Colon), node) => {
-                    member.insert(key, node);
+    /// 回復モードでは`error`を記録し、同じ階層のcommaか閉じ括弧まで読み飛ばす
+    /// 回復モードでない場合やobject/arrayの外のエラーは、そのまま`error`を返す
+    fn recover(&mut self, error: ParseError, container: Option<&Container>) -> Result<Recovery> {
+        let (Some(errors), Some(container)) = (self.errors.as_mut(), container) else {
+            return Err(error);
+        };
+        let mut nesting = match (&error, container) {
+            // 読み取り済みの対応する閉じ括弧は、読み取り中のobject/arrayを閉じる
+            (ParseError::UnexpectedToken(Token::CloseBrace, _), Container::Object(..))
+            | (ParseError::UnexpectedToken(Token::CloseBracket, _), Container::Array(..)) => {
+                errors.push(error);
+                return Ok(Recovery::Close);
+            }
+            // 読み取り済みの開き括弧は、対応する閉じ括弧まで読み飛ばす
+            (ParseError::UnexpectedToken(Token::OpenBrace | Token::OpenBracket, _), _)
+            | (ParseError::MaxDepthExceeded(..), _) => 1,
+            _ => 0,
+        };
+        errors.push(error);
+        while let Some(token) = self.tokens.peek() {
+            match token.value {
+                Token::Comma if nesting == 0 => {
+                    self.tokens.next();
+                    return Ok(Recovery::AfterComma);
                 }
-                _ => return Err(ParseError::UnexpectedConsumedUpToken.into()),
+                Token::CloseBrace | Token::CloseBracket if nesting == 0 => {
+                    return Ok(Recovery::Resume);
+                }
+                Token::OpenBrace | Token::OpenBracket => nesting += 1,
+                Token::CloseBrace | Token::CloseBracket => nesting -= 1,
+                _ => (),
             }
+            self.tokens.next();
+        }
+        Ok(Recovery::End)
+    }
 
-            times += 1;
+    /// scalarはNodeを返し、object/arrayはstackに積んで`None`を返す
+    fn start_value(
+        &mut self,
+        token: Spanned<Token>,
+        stack: &mut Vec<Container>,
+    ) -> Result<Option<Node>> {
+        if let Some(collector) = self.collector.as_mut() {
+            collector.attach_leading();
         }
-        Ok(Node::Object(member))
+        let node = match token.value {
+            Token::StringValue(value) => Node::StringValue(value),
+            Token::Number(value) => Node::Number(value),
+            Token::Boolean(value) => Node::Boolean(value),
+            Token::Null => Node::Null,
+            Token::Identifier(value) if self.options.allow_identifier_values => {
+                Node::StringValue(value)
+            }
+            Token::OpenBrace => {
+                self.enter_nest(token.position)?;
+                stack.push(Container::Object(BTreeMap::new(), None, token.position));
+                return Ok(None);
+            }
+            Token::OpenBracket => {
+                self.enter_nest(token.position)?;
+                stack.push(Container::Array(vec![], token.position));
+                return Ok(None);
+            }
+            Token::Colon | Token::Comma => {
+                return Err(ParseError::UnexpectedSeparator(token.value, token.position));
+            }
+            _ => return Err(unexpected_closing(token, stack.last())),
+        };
+        Ok(Some(node))
     }
 
-    fn parse_array(&mut self) -> Result<Node> {
-        let mut times = 0;
-        let mut result = vec![];
-        loop {
-            let first_token = self.next_grammar().ok_or(ParseError::UnClosedToken)?;
-            let value = match first_token {
-                Token::CloseBracket => break,
-                Token::Comma => {
-                    // 0回目の時はcommaはなし
-                    if times == 0 {
-                        return Err(ParseError::UnexpectedToken(
-                            "first comma is not allowed".to_string(),
-                        )
-                        .into());
-                    } else {
-                        let token = self.next_grammar().ok_or(ParseError::UnClosedToken)?;
-                        if token == Token::CloseBracket {
-                            break;
-                        };
-                        token
+    /// object/arrayの次の要素の値の最初のtokenを返す、閉じ括弧であれば`None`
+    /// objectの場合はkeyと`:`まで読み進める
+    /// `after_comma`の場合は、commaを読み取り済みとして扱う
+    fn next_element(
+        &mut self,
+        container: &mut Container,
+        after_comma: bool,
+    ) -> Result<Option<Spanned<Token>>> {
+        let first = match container {
+            Container::Object(members, ..) => members.is_empty(),
+            Container::Array(items, _) => items.is_empty(),
+        };
+        // close,comma,値(objectはkey)のいづれか
+        let token = match self.next_closing(container)? {
+            None => return Ok(None),
+            Some(token) if after_comma => token,
+            Some(token) if token.value == Token::Comma => {
+                // 0回目の時はcommaはなし
+                if first {
+                    return Err(ParseError::UnexpectedSeparator(token.value, token.position));
+                }
+                match self.next_closing(container)? {
+                    None if !self.options.allow_trailing_commas => {
+                        return Err(ParseError::TrailingCommaNotAllowed(token.position));
                     }
+                    None => return Ok(None),
+                    Some(token) => token,
                 }
-                _ => first_token,
-            };
+            }
+            Some(token) if !first && self.options.require_commas => {
+                return Err(ParseError::MissingComma(token.position));
+            }
+            Some(token) => token,
+        };
 
-            times += 1;
-
-            match value {
-                Token::StringValue(value) => result.push(Node::StringValue(value)),
-                Token::Number(value) => result.push(Node::Number(value)),
-                Token::Boolean(value) => result.push(Node::Boolean(value)),
-                Token::Null => result.push(Node::Null),
-                Token::OpenBrace => result.push(self.parse_object()?),
-                Token::OpenBracket => result.push(self.parse_array()?),
-                _ => {
-                    return Err(ParseError::UnexpectedToken(
-                        "found an unexpected token while parsing the array".to_string(),
-                    )
-                    .into())
+        match container {
+            Container::Array(items, _) => {
+                if let Some(collector) = self.collector.as_mut() {
+                    collector.path.push(PathSegment::Index(items.len()));
+                }
+                Ok(Some(token))
+            }
+            Container::Object(_, pending_key, _) => {
+                let key = self.to_key(token)?;
+                if let Some(collector) = self.collector.as_mut() {
+                    collector.path.push(PathSegment::Key(key.clone()));
+                    collector.attach_leading();
+                }
+                match self.next_grammar()? {
+                    Some(token) if token.value == Token::Colon => (),
+                    Some(token) => return Err(unexpected(token)),
+                    None => return Err(ParseError::UnexpectedConsumedUpToken(self.last_position)),
                 }
+                *pending_key = Some(key);
+                let token = self
+                    .next_grammar()?
+                    .ok_or(ParseError::UnexpectedConsumedUpToken(self.last_position))?;
+                Ok(Some(token))
             }
         }
-        Ok(Node::Array(result))
+    }
+
+    /// 次のgrammarが`container`の閉じ括弧であれば`None`を返す
+    /// 入力が終わっていればUnClosedToken、対応しない閉じ括弧であればUnexpectedClosing
+    fn next_closing(&mut self, container: &Container) -> Result<Option<Spanned<Token>>> {
+        let token = self.next_grammar()?.ok_or_else(|| container.unclosed())?;
+        match token.value {
+            ref value if *value == container.close() => {
+                if let Some(collector) = self.collector.as_mut() {
+                    collector.attach_trailing();
+                }
+                Ok(None)
+            }
+            Token::CloseBrace | Token::CloseBracket => {
+                Err(ParseError::UnexpectedClosing(token.value, token.position))
+            }
+            _ => Ok(Some(token)),
+        }
+    }
+
+    fn leave_path(&mut self) {
+        if let Some(collector) = self.collector.as_mut() {
+            collector.path.pop();
+        }
+    }
+
+    fn enter_nest(&mut self, position: Position) -> Result<()> {
+        if self.depth >= self.options.max_depth {
+            return Err(ParseError::MaxDepthExceeded(
+                self.options.max_depth,
+                position,
+            ));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// key tokenはstringのみ許容 https://www.rfc-editor.org/rfc/rfc8259#section-4
+    /// lenient modeではNumberも許容する
+    /// `Token::Identifier`はJSON5のquoteされていないkeyとして許容する
+    fn to_key(&self, token: Spanned<Token>) -> Result<String> {
+        match token.value {
+            Token::StringValue(key) | Token::Identifier(key) => Ok(key),
+            Token::Number(key) if self.options.allow_number_keys => Ok(key),
+            _ => Err(unexpected(token)),
+        }
     }
 
     /// 次のgrammarまで読み飛ばす
-    fn next_grammar(&mut self) -> Option<Token> {
-        // todo nextするのかどうか、検討の余地あり
-        while let Some(token) = self.tokens.next() {
-            match token {
-                Token::BreakLine => { /* skip */ }
-                Token::WhiteSpaces(_) => { /* skip */ }
-                Token::CommentBlock(_) => { /* skip */ }
-                Token::CommentLine(_) => { /* skip */ }
-                _ => return Some(token.clone()),
+    /// コメントを保持する場合は、読み飛ばしたコメントを溜めておく
+    /// コメントを許容しない場合はCommentsNotAllowed
+    fn next_grammar(&mut self) -> Result<Option<Spanned<Token>>> {
+        for token in self.tokens.by_ref() {
+            self.last_position = token.position;
+            let comment = match token.value {
+                Token::BreakLine | Token::WhiteSpaces(_) | Token::Tabs(_) => continue,
+                Token::CommentBlock(_) | Token::CommentLine(_) if !self.options.allow_comments => {
+                    return Err(ParseError::CommentsNotAllowed(token.position));
+                }
+                Token::CommentBlock(text) => Comment::Block(text),
+                Token::CommentLine(text) => Comment::Line(text),
+                _ => return Ok(Some(token)),
             };
+            if let Some(collector) = self.collector.as_mut() {
+                collector.pending.push(comment);
+            }
         }
-        None
+        Ok(None)
+    }
+}
+
+/// 回復モードでエラーの後に読み進める位置
+enum Recovery {
+    Resume,     // 閉じ括弧の直前
+    AfterComma, // commaの直後
+    Close,      // 読み取り中のobject/arrayを閉じる
+    End,        // 入力の終わり
+}
+
+/// 読み取り中のobject/arrayと、その開き括弧の位置
+enum Container {
+    Object(BTreeMap<String, Node>, Option<String>, Position), // 値を読み取り中のmemberのkey
+    Array(Vec<Node>, Position),
+}
+
+impl Container {
+    fn push(&mut self, value: Node) {
+        match self {
+            Container::Object(members, key, _) => {
+                let key = key.take().expect("objectの値の前にkeyを読み取っている");
+                members.insert(key, value);
+            }
+            Container::Array(items, _) => items.push(value),
+        }
+    }
+
+    fn close(&self) -> Token {
+        match self {
+            Container::Object(..) => Token::CloseBrace,
+            Container::Array(..) => Token::CloseBracket,
+        }
+    }
+
+    fn unclosed(&self) -> ParseError {
+        match self {
+            Container::Object(.., position) => {
+                ParseError::UnClosedToken(Token::OpenBrace, *position)
+            }
+            Container::Array(_, position) => {
+                ParseError::UnClosedToken(Token::OpenBracket, *position)
+            }
+        }
+    }
+
+    fn into_node(self) -> Node {
+        match self {
+            Container::Object(members, ..) => Node::Object(members),
+            Container::Array(items, _) => Node::Array(items),
+        }
+    }
+}
+
+fn container_name(open: &Token) -> &'static str {
+    match open {
+        Token::OpenBrace => "object",
+        _ => "array",
+    }
+}
+
+fn separator_char(separator: &Token) -> char {
+    match separator {
+        Token::Colon => ':',
+        _ => ',',
+    }
+}
+
+fn unexpected(token: Spanned<Token>) -> ParseError {
+    ParseError::UnexpectedToken(token.value, token.position)
+}
+
+/// 読み取り中のobject/array(`container`)に対応しない閉じ括弧はUnexpectedClosing
+/// 対応する閉じ括弧は値が足りないため、それ以外のtokenと同じくUnexpectedToken
+fn unexpected_closing(token: Spanned<Token>, container: Option<&Container>) -> ParseError {
+    match token.value {
+        Token::CloseBrace | Token::CloseBracket
+            if container.map(Container::close) != Some(token.value.clone()) =>
+        {
+            ParseError::UnexpectedClosing(token.value, token.position)
+        }
+        _ => unexpected(token),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::node::NodeStats;
+    use crate::options::DEFAULT_MAX_DEPTH;
+    use crate::token::Location;
     use std::collections::BTreeMap;
 
+    /// parserのテストでは、tokenの順番を位置とする
+    fn spanned(tokens: Vec<Token>) -> Vec<Spanned<Token>> {
+        tokens
+            .into_iter()
+            .enumerate()
+            .map(|(index, token)| Spanned::new(token, Location(index, index), at(index)))
+            .collect()
+    }
+
+    /// `index`番目のtokenの位置
+    fn at(index: usize) -> Position {
+        Position {
+            line: 1,
+            column: index + 1,
+        }
+    }
+
     fn assert_parse(data: Vec<(Vec<Token>, Node)>) {
-        for (tokens, expect) in data.iter() {
-            let mut parser = Parser::new(tokens);
+        for (tokens, expect) in data {
+            let mut parser = Parser::new(spanned(tokens));
             let result = parser.parse();
             match result {
-                Ok(node) => assert_eq!(*expect, node),
+                Ok(node) => assert_eq!(expect, node),
                 Err(e) => panic!("[assert_parse]: {}", e),
             }
         }
     }
 
     fn assert_parse_err(data: Vec<Token>, expect: ParseError) {
-        let mut parser = Parser::new(&data);
+        let mut parser = Parser::new(spanned(data));
         let result = parser.parse();
         assert!(result.is_err());
         let err = result.err().unwrap();
-        assert_eq!(expect, *err.downcast_ref::<ParseError>().unwrap());
+        assert_eq!(expect, err);
     }
 
     #[test]
@@ -224,14 +578,11 @@ mod tests {
     #[test]
     fn parse_single_value_no_token_error() {
         let data = vec![];
-        let mut parser = Parser::new(&data);
+        let mut parser = Parser::new(spanned(data));
         let result = parser.parse();
         assert!(result.is_err());
         let err = result.err().unwrap();
-        assert_eq!(
-            ParseError::NotFoundToken,
-            *err.downcast_ref::<ParseError>().unwrap()
-        )
+        assert_eq!(ParseError::NotFoundToken, err)
     }
 
     #[test]
@@ -242,10 +593,26 @@ mod tests {
         ];
         assert_parse_err(
             data,
-            ParseError::UnexpectedToken("contains multiple values".to_string()),
+            ParseError::UnexpectedToken(Token::StringValue("test".to_string()), at(1)),
         );
     }
 
+    /// `{"user": {"name":"sato"}}`
+    fn nested_object_tokens() -> Vec<Token> {
+        vec![
+            Token::OpenBrace,
+            Token::StringValue("user".to_string()),
+            Token::Colon,
+            Token::WhiteSpaces(1),
+            Token::OpenBrace,
+            Token::StringValue("name".to_string()),
+            Token::Colon,
+            Token::StringValue("sato".to_string()),
+            Token::CloseBrace,
+            Token::CloseBrace,
+        ]
+    }
+
     #[test]
     fn parse_object_value() {
         let data_expect_list = vec![
@@ -276,18 +643,7 @@ mod tests {
             ),
             // nested
             (
-                vec![
-                    Token::OpenBrace,
-                    Token::StringValue("user".to_string()),
-                    Token::Colon,
-                    Token::WhiteSpaces(1),
-                    Token::OpenBrace,
-                    Token::StringValue("name".to_string()),
-                    Token::Colon,
-                    Token::StringValue("sato".to_string()),
-                    Token::CloseBrace,
-                    Token::CloseBrace,
-                ],
+                nested_object_tokens(),
                 Node::Object(BTreeMap::from([(
                     "user".to_string(),
                     Node::Object(BTreeMap::from([(
@@ -316,6 +672,24 @@ mod tests {
         assert_parse(data_expect_list);
     }
 
+    #[test]
+    fn parse_nested_object_stats() {
+        let node = Parser::new(spanned(nested_object_tokens()))
+            .parse()
+            .expect("parseできます。");
+        assert_eq!(
+            NodeStats {
+                max_depth: 2,
+                nodes: 3,
+                objects: 2,
+                strings: 1,
+                members: 2,
+                ..NodeStats::default()
+            },
+            node.stats()
+        );
+    }
+
     #[test]
     fn parse_object_value_not_closed() {
         let data = vec![
@@ -329,7 +703,7 @@ mod tests {
             Token::BreakLine,
             // Token::CloseBrace,
         ];
-        assert_parse_err(data, ParseError::UnClosedToken);
+        assert_parse_err(data, ParseError::UnClosedToken(Token::OpenBrace, at(0)));
     }
 
     #[test]
@@ -345,10 +719,7 @@ mod tests {
             Token::Comma,
             Token::CloseBrace,
         ];
-        assert_parse_err(
-            data,
-            ParseError::UnexpectedToken("found a Token that cannot be a key".to_string()),
-        );
+        assert_parse_err(data, ParseError::UnexpectedToken(Token::Colon, at(3)));
     }
 
     #[test]
@@ -362,9 +733,99 @@ mod tests {
             Token::CloseBrace,
         ];
 
+        assert_parse_err(data, ParseError::UnexpectedToken(Token::CloseBrace, at(5)));
+    }
+
+    #[test]
+    fn parse_object_number_key_in_lenient_mode() {
+        // {1:"a", 2:"b"}
+        let data = vec![
+            Token::OpenBrace,
+            Token::Number("1".to_string()),
+            Token::Colon,
+            Token::StringValue("a".to_string()),
+            Token::Comma,
+            Token::WhiteSpaces(1),
+            Token::Number("2".to_string()),
+            Token::Colon,
+            Token::StringValue("b".to_string()),
+            Token::CloseBrace,
+        ];
+        let mut parser = Parser::with_options(
+            spanned(data.clone()),
+            ParseOptions::new().allow_number_keys(true),
+        );
+        let node = parser
+            .parse()
+            .expect("lenient modeではNumberのkeyを許容します。");
+        assert_eq!(
+            Node::Object(BTreeMap::from([
+                ("1".to_string(), Node::StringValue("a".to_string())),
+                ("2".to_string(), Node::StringValue("b".to_string())),
+            ])),
+            node
+        );
+
         assert_parse_err(
             data,
-            ParseError::UnexpectedToken("contains a token other than the value".to_string()),
+            ParseError::UnexpectedToken(Token::Number("1".to_string()), at(1)),
+        );
+    }
+
+    #[test]
+    fn parse_object_identifier_key() {
+        // {name: "sato"}
+        let data = vec![(
+            vec![
+                Token::OpenBrace,
+                Token::Identifier("name".to_string()),
+                Token::Colon,
+                Token::WhiteSpaces(1),
+                Token::StringValue("sato".to_string()),
+                Token::CloseBrace,
+            ],
+            Node::Object(BTreeMap::from([(
+                "name".to_string(),
+                Node::StringValue("sato".to_string()),
+            )])),
+        )];
+        assert_parse(data);
+    }
+
+    #[test]
+    fn parse_identifier_value() {
+        // {name: sato}
+        let data = vec![
+            Token::OpenBrace,
+            Token::Identifier("name".to_string()),
+            Token::Colon,
+            Token::Identifier("sato".to_string()),
+            Token::CloseBrace,
+        ];
+        let mut parser = Parser::with_options(
+            spanned(data.clone()),
+            ParseOptions::new().allow_identifier_values(true),
+        );
+        let node = parser.parse().expect("Identifierの値を許容します。");
+        assert_eq!(
+            Node::Object(BTreeMap::from([(
+                "name".to_string(),
+                Node::StringValue("sato".to_string()),
+            )])),
+            node
+        );
+
+        assert_parse_err(
+            data,
+            ParseError::UnexpectedToken(Token::Identifier("sato".to_string()), at(3)),
+        );
+        assert_parse_err(
+            vec![
+                Token::OpenBracket,
+                Token::Identifier("sato".to_string()),
+                Token::CloseBracket,
+            ],
+            ParseError::UnexpectedToken(Token::Identifier("sato".to_string()), at(1)),
         );
     }
 
@@ -430,6 +891,264 @@ mod tests {
         assert_parse(data_expect_list);
     }
 
+    #[test]
+    fn parse_missing_comma_in_strict_mode() {
+        // {"a":1 "b":2}
+        let object = vec![
+            Token::OpenBrace,
+            Token::StringValue("a".to_string()),
+            Token::Colon,
+            Token::Number("1".to_string()),
+            Token::WhiteSpaces(1),
+            Token::StringValue("b".to_string()),
+            Token::Colon,
+            Token::Number("2".to_string()),
+            Token::CloseBrace,
+        ];
+        // [1 2]
+        let array = vec![
+            Token::OpenBracket,
+            Token::Number("1".to_string()),
+            Token::WhiteSpaces(1),
+            Token::Number("2".to_string()),
+            Token::CloseBracket,
+        ];
+        // commaがあるべき位置の次のtokenを指す
+        for (data, position) in [(object, at(5)), (array, at(3))] {
+            let mut parser = Parser::with_options(
+                spanned(data.clone()),
+                ParseOptions::new().require_commas(true),
+            );
+            let err = parser.parse().expect_err("commaの欠落はErrになります。");
+            assert_eq!(ParseError::MissingComma(position), err);
+            assert!(Parser::new(spanned(data)).parse().is_ok());
+        }
+
+        // trailing commaは許容する
+        let data = vec![
+            Token::OpenBracket,
+            Token::Number("1".to_string()),
+            Token::Comma,
+            Token::Number("2".to_string()),
+            Token::Comma,
+            Token::CloseBracket,
+        ];
+        let mut parser =
+            Parser::with_options(spanned(data), ParseOptions::new().require_commas(true));
+        assert!(parser.parse().is_ok());
+    }
+
+    #[test]
+    fn parse_with_comments_should_attach_to_following_node() {
+        // /*a*/{"k"://b
+        // [1,/*c*/2/*d*/]}//e
+        let data = vec![
+            Token::CommentBlock("a".to_string()),
+            Token::OpenBrace,
+            Token::StringValue("k".to_string()),
+            Token::Colon,
+            Token::CommentLine("b".to_string()),
+            Token::BreakLine,
+            Token::OpenBracket,
+            Token::Number("1".to_string()),
+            Token::Comma,
+            Token::CommentBlock("c".to_string()),
+            Token::Number("2".to_string()),
+            Token::CommentBlock("d".to_string()),
+            Token::CloseBracket,
+            Token::CloseBrace,
+            Token::CommentLine("e".to_string()),
+        ];
+        let mut parser = Parser::new(spanned(data.clone()));
+        let (node, comments) = parser.parse_with_comments().expect("parseできます。");
+        assert_eq!(Parser::new(spanned(data)).parse().unwrap(), node);
+        assert_eq!(&[Comment::Block("a".to_string())], comments.leading(""));
+        assert_eq!(&[Comment::Line("b".to_string())], comments.leading("/k"));
+        assert!(comments.leading("/k/0").is_empty());
+        assert_eq!(&[Comment::Block("c".to_string())], comments.leading("/k/1"));
+        assert_eq!(&[Comment::Block("d".to_string())], comments.trailing("/k"));
+        assert_eq!(&[Comment::Line("e".to_string())], comments.end());
+    }
+
+    #[test]
+    fn parse_nested_value_max_depth() {
+        let data = vec![Token::OpenBracket; 10_000];
+        assert_parse_err(
+            data,
+            ParseError::MaxDepthExceeded(DEFAULT_MAX_DEPTH, at(DEFAULT_MAX_DEPTH)),
+        );
+
+        // {"a":[[1]]}
+        let data = vec![
+            Token::OpenBrace,
+            Token::StringValue("a".to_string()),
+            Token::Colon,
+            Token::OpenBracket,
+            Token::OpenBracket,
+            Token::Number("1".to_string()),
+            Token::CloseBracket,
+            Token::CloseBracket,
+            Token::CloseBrace,
+        ];
+        let mut parser =
+            Parser::with_options(spanned(data.clone()), ParseOptions::new().max_depth(3));
+        assert!(parser.parse().is_ok());
+        let mut parser = Parser::with_options(spanned(data), ParseOptions::new().max_depth(2));
+        let err = parser
+            .parse()
+            .expect_err("max_depthを超えるとErrになります。");
+        assert_eq!(ParseError::MaxDepthExceeded(2, at(4)), err);
+    }
+
+    #[test]
+    fn parse_deeply_nested_array_without_recursion() {
+        let depth = 50_000;
+        let mut data = vec![Token::OpenBracket; depth];
+        data.extend(vec![Token::CloseBracket; depth]);
+        let mut parser = Parser::with_options(spanned(data), ParseOptions::new().max_depth(depth));
+        let node = parser.parse().expect("max_depthまではparseできます。");
+
+        // Nodeの破棄は再帰するため、1段ずつ取り出して破棄する
+        let mut stack = vec![node];
+        let mut nested = 0;
+        while let Some(mut node) = stack.pop() {
+            if let Node::Array(items) = &mut node {
+                nested += 1;
+                stack.append(items);
+            }
+        }
+        assert_eq!(depth, nested);
+    }
+
+    #[test]
+    fn parse_unexpected_separator() {
+        // [,1]
+        let data = vec![
+            Token::OpenBracket,
+            Token::Comma,
+            Token::Number("1".to_string()),
+            Token::CloseBracket,
+        ];
+        assert_parse_err(data, ParseError::UnexpectedSeparator(Token::Comma, at(1)));
+        // {"a"::1}
+        let data = vec![
+            Token::OpenBrace,
+            Token::StringValue("a".to_string()),
+            Token::Colon,
+            Token::Colon,
+            Token::Number("1".to_string()),
+            Token::CloseBrace,
+        ];
+        assert_parse_err(data, ParseError::UnexpectedSeparator(Token::Colon, at(3)));
+        assert_parse_err(
+            vec![Token::Colon],
+            ParseError::UnexpectedSeparator(Token::Colon, at(0)),
+        );
+    }
+
+    #[test]
+    fn parse_unexpected_closing() {
+        assert_parse_err(
+            vec![Token::CloseBracket],
+            ParseError::UnexpectedClosing(Token::CloseBracket, at(0)),
+        );
+        let data = vec![
+            Token::OpenBracket,
+            Token::Number("1".to_string()),
+            Token::CloseBracket,
+            Token::CloseBracket,
+        ];
+        assert_parse_err(
+            data,
+            ParseError::UnexpectedClosing(Token::CloseBracket, at(3)),
+        );
+        let data = vec![
+            Token::OpenBrace,
+            Token::StringValue("a".to_string()),
+            Token::Colon,
+            Token::OpenBracket,
+            Token::Number("1".to_string()),
+            Token::CloseBrace,
+        ];
+        assert_parse_err(
+            data,
+            ParseError::UnexpectedClosing(Token::CloseBrace, at(5)),
+        );
+        // 対応する閉じ括弧は値が足りないエラー
+        let data = vec![
+            Token::OpenBrace,
+            Token::StringValue("a".to_string()),
+            Token::Colon,
+            Token::CloseBrace,
+        ];
+        assert_parse_err(data, ParseError::UnexpectedToken(Token::CloseBrace, at(3)));
+    }
+
+    #[test]
+    fn parse_recovering_should_skip_to_next_element() {
+        // [[1 }, {"a": [2, 3], "b": ]}, {: 4}, 5, ]
+        // 対応しない閉じ括弧は読み飛ばすため、2つ目以降も内側のarrayの要素になる
+        let data = vec![
+            Token::OpenBracket,
+            Token::OpenBracket,
+            Token::Number("1".to_string()),
+            Token::CloseBrace,
+            Token::Comma,
+            Token::OpenBrace,
+            Token::StringValue("a".to_string()),
+            Token::Colon,
+            Token::OpenBracket,
+            Token::Number("2".to_string()),
+            Token::Comma,
+            Token::Number("3".to_string()),
+            Token::CloseBracket,
+            Token::Comma,
+            Token::StringValue("b".to_string()),
+            Token::Colon,
+            Token::CloseBracket,
+            Token::CloseBrace,
+            Token::Comma,
+            Token::OpenBrace,
+            Token::Colon,
+            Token::Number("4".to_string()),
+            Token::CloseBrace,
+            Token::Comma,
+            Token::Number("5".to_string()),
+            Token::Comma,
+            Token::CloseBracket,
+        ];
+        let mut parser = Parser::new(spanned(data));
+        let (node, errors) = parser.parse_recovering();
+        assert_eq!(
+            vec![
+                ParseError::UnexpectedClosing(Token::CloseBrace, at(3)),
+                ParseError::UnexpectedClosing(Token::CloseBracket, at(16)),
+                ParseError::UnexpectedToken(Token::Colon, at(20)),
+                ParseError::UnClosedToken(Token::OpenBracket, at(0)),
+            ],
+            errors
+        );
+        assert_eq!(
+            r#"[[1,{"a":[2,3]},{},5]]"#,
+            node.expect("回復したNodeを返します。").to_json_string()
+        );
+
+        // 入力の終わりでは読み取り中のobject/arrayを閉じる
+        let data = vec![
+            Token::OpenBracket,
+            Token::OpenBrace,
+            Token::StringValue("a".to_string()),
+            Token::Colon,
+            Token::Number("1".to_string()),
+        ];
+        let (node, errors) = Parser::new(spanned(data)).parse_recovering();
+        assert_eq!(
+            vec![ParseError::UnClosedToken(Token::OpenBrace, at(1))],
+            errors
+        );
+        assert_eq!(r#"[{"a":1}]"#, node.unwrap().to_json_string());
+    }
+
     #[test]
     fn parse_array_value_invalid() {
         let data = vec![
@@ -439,6 +1158,6 @@ mod tests {
             Token::StringValue("hoge".to_string()),
             Token::Comma,
         ];
-        assert_parse_err(data, ParseError::UnClosedToken);
+        assert_parse_err(data, ParseError::UnClosedToken(Token::OpenBracket, at(0)));
     }
 }
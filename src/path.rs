@@ -0,0 +1,86 @@
+/// JSON上の位置を表すsegment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// rootからの経路
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsonPath(Vec<PathSegment>);
+
+impl JsonPath {
+    pub fn new() -> Self {
+        JsonPath(vec![])
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    /// 直近のsegmentがobjectのkeyであれば、そのkey
+    pub fn last_key(&self) -> Option<&str> {
+        match self.0.last() {
+            Some(PathSegment::Key(key)) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// JSON Pointer(RFC 6901)形式の文字列
+    pub fn to_pointer(&self) -> String {
+        self.0
+            .iter()
+            .map(|segment| match segment {
+                PathSegment::Key(key) => format!("/{}", escape_pointer_segment(key)),
+                PathSegment::Index(index) => format!("/{}", index),
+            })
+            .collect()
+    }
+
+    pub(crate) fn push(&mut self, segment: PathSegment) {
+        self.0.push(segment);
+    }
+
+    pub(crate) fn pop(&mut self) {
+        self.0.pop();
+    }
+}
+
+/// JSON Pointer(RFC 6901)のsegmentとしてescapeする
+pub(crate) fn escape_pointer_segment(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// JSON Pointer(RFC 6901)のsegmentをunescapeする
+/// `~01`が`~1`になるよう、`~1`を先に置き換える
+pub(crate) fn unescape_pointer_segment(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_pointer_should_escape_segments() {
+        let mut path = JsonPath::new();
+        assert_eq!("", path.to_pointer());
+        path.push(PathSegment::Key("a/b".to_string()));
+        path.push(PathSegment::Index(0));
+        path.push(PathSegment::Key("c~d".to_string()));
+        assert_eq!("/a~1b/0/c~0d", path.to_pointer());
+        assert_eq!(Some("c~d"), path.last_key());
+        path.pop();
+        assert_eq!(None, path.last_key());
+    }
+
+    #[test]
+    fn unescape_pointer_segment_should_reverse_escape() {
+        assert_eq!("a/b", unescape_pointer_segment("a~1b"));
+        assert_eq!("~1", unescape_pointer_segment("~01"));
+        assert_eq!(
+            "c~d",
+            unescape_pointer_segment(&escape_pointer_segment("c~d"))
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! `serde`featureで有効になる`serde_json::Value`との変換
+use crate::escape::{escape_string, unescape_string};
+use crate::node::Node;
+use crate::parser::ParseError;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FromStrError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Deserialize(#[from] serde_json::Error),
+}
+
+/// JSONCをparseし、`Deserialize`を実装した型に変換する
+/// `serde_json::Value`を経由するため、数値は`number_to_value`と同じ規則で変換してから
+/// 要求された型に変換する
+/// ```
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     port: u16,
+/// }
+/// let config: Config = jsonc_wasm::from_str("{\"port\": 8080, // comment\n}").unwrap();
+/// assert_eq!(8080, config.port);
+/// ```
+pub fn from_str<T: DeserializeOwned>(input: &str) -> Result<T, FromStrError> {
+    let node = crate::parse(input)?;
+    Ok(serde_json::from_value(Value::from(node))?)
+}
+
+/// `Node::Number`は次の順で`serde_json::Number`に変換する
+/// - `i64`/`u64`に収まる整数はそのまま
+/// - それ以外(小数、指数表記、`i64`/`u64`に収まらない整数)は`f64`として変換するため、精度が落ちる場合がある
+/// - `f64`でも表現できない(overflowする)値は、元の文字列を`Value::String`として保持する
+impl From<Node> for Value {
+    fn from(node: Node) -> Self {
+        match node {
+            Node::StringValue(value) => Value::String(unescape_string(&value)),
+            Node::Number(value) => number_to_value(value),
+            Node::Boolean(value) => Value::Bool(value),
+            Node::Null => Value::Null,
+            Node::Array(items) => Value::Array(items.into_iter().map(Value::from).collect()),
+            Node::Object(members) => Value::Object(
+                members
+                    .into_iter()
+                    .map(|(key, value)| (unescape_string(&key), Value::from(value)))
+                    .collect::<Map<String, Value>>(),
+            ),
+        }
+    }
+}
+
+impl From<Value> for Node {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::String(value) => Node::StringValue(escape_string(&value)),
+            Value::Number(value) => Node::Number(value.to_string()),
+            Value::Bool(value) => Node::Boolean(value),
+            Value::Null => Node::Null,
+            Value::Array(items) => Node::Array(items.into_iter().map(Node::from).collect()),
+            Value::Object(members) => Node::Object(
+                members
+                    .into_iter()
+                    .map(|(key, value)| (escape_string(&key), Node::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+fn number_to_value(value: String) -> Value {
+    if let Ok(number) = value.parse::<i64>() {
+        return Value::Number(number.into());
+    }
+    if let Ok(number) = value.parse::<u64>() {
+        return Value::Number(number.into());
+    }
+    match value.parse::<f64>().ok().and_then(Number::from_f64) {
+        Some(number) => Value::Number(number),
+        None => Value::String(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn node_to_value() {
+        let node = Node::Object(BTreeMap::from([
+            (
+                "name".to_string(),
+                Node::StringValue(r#"sa\"toあ"#.to_string()),
+            ),
+            ("age".to_string(), Node::Number("20".to_string())),
+            ("flag".to_string(), Node::Boolean(false)),
+            ("attr".to_string(), Node::Null),
+            (
+                "list".to_string(),
+                Node::Array(vec![
+                    Node::Number("-1".to_string()),
+                    Node::Number("18446744073709551615".to_string()),
+                    Node::Number("1.5e2".to_string()),
+                ]),
+            ),
+        ]));
+        assert_eq!(
+            json!({
+                "name": "sa\"toあ",
+                "age": 20,
+                "flag": false,
+                "attr": null,
+                "list": [-1, 18446744073709551615u64, 150.0],
+            }),
+            Value::from(node)
+        );
+    }
+
+    #[test]
+    fn number_out_of_range_to_value() {
+        // i64/u64に収まらない整数はf64になる
+        assert_eq!(
+            json!(1e20),
+            Value::from(Node::Number("100000000000000000000".to_string()))
+        );
+        // f64でもoverflowする値は文字列として保持する
+        assert_eq!(
+            json!("1e400"),
+            Value::from(Node::Number("1e400".to_string()))
+        );
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Config {
+        name: String,
+        servers: Vec<Server>,
+        ratio: f64,
+        timeout: Option<u32>,
+    }
+
+    #[test]
+    fn from_str_should_deserialize_into_struct() {
+        let input = r#"{
+            // comment
+            "name": "app\u3042",
+            "servers": [
+                {"host": "localhost", "port": 8080,},
+            ],
+            "ratio": 1.5e-1,
+        }"#;
+        assert_eq!(
+            Config {
+                name: "appあ".to_string(),
+                servers: vec![Server {
+                    host: "localhost".to_string(),
+                    port: 8080,
+                }],
+                ratio: 0.15,
+                timeout: None,
+            },
+            from_str::<Config>(input).expect("deserializeできます。")
+        );
+        assert!(matches!(
+            from_str::<Config>("{"),
+            Err(FromStrError::Parse(_))
+        ));
+        assert!(matches!(
+            from_str::<Config>(r#"{"name": 1}"#),
+            Err(FromStrError::Deserialize(_))
+        ));
+    }
+
+    #[test]
+    fn value_to_node() {
+        let value = json!({"a": [1, "x\"y", true, null], "b": {"c": 1.5}});
+        assert_eq!(
+            Node::Object(BTreeMap::from([
+                (
+                    "a".to_string(),
+                    Node::Array(vec![
+                        Node::Number("1".to_string()),
+                        Node::StringValue(r#"x\"y"#.to_string()),
+                        Node::Boolean(true),
+                        Node::Null,
+                    ]),
+                ),
+                (
+                    "b".to_string(),
+                    Node::Object(BTreeMap::from([(
+                        "c".to_string(),
+                        Node::Number("1.5".to_string()),
+                    )])),
+                ),
+            ])),
+            Node::from(value.clone())
+        );
+        assert_eq!(value, Value::from(Node::from(value.clone())));
+    }
+}
@@ -0,0 +1,706 @@
+use crate::comment::{Comment, Comments};
+use crate::escape::decode_unicode_escapes;
+use crate::node::Node;
+use crate::path::{JsonPath, PathSegment};
+use crate::utils::{canonical_number, is_json_number};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+/// 値の出力を差し替えるcallback
+/// `Some`を返した場合、その文字列をそのまま出力する
+pub type ValueFormatter = fn(&JsonPath, &Node) -> Option<String>;
+
+#[derive(Debug, Clone, Default)]
+pub struct SerializeOptions {
+    formatter: Option<ValueFormatter>,
+    diff_anchors: bool,
+    spaced: bool,
+    unquote_numeric_strings: bool,
+    array_sort_keys: Vec<(String, String)>, // (arrayのJSON Pointer, 並び替えに使うkey)
+    indent: Option<usize>,
+    escape_forward_slashes: bool,
+    canonical_numbers: bool,
+    decode_unicode_escapes: bool,
+}
+
+impl SerializeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 1行のまま`:`と`,`の後ろに空白を入れるpreset
+    /// `{"a": 1, "b": [2, 3]}`
+    pub fn compact_spaced() -> Self {
+        Self::new().spaced(true)
+    }
+
+    /// 2spaceでindentした複数行のpreset
+    pub fn pretty() -> Self {
+        Self::new().indent(2)
+    }
+
+    pub fn formatter(mut self, formatter: ValueFormatter) -> Self {
+        self.formatter = Some(formatter);
+        self
+    }
+
+    /// rootのobjectの各memberの後ろに、key pathから求めたhashのコメント(`// #1a2b3c4d`)を出力する
+    /// 生成された設定の3-way mergeで、keyを追跡するための目印として使う
+    /// 行コメントのため、rootのobjectはmemberごとに改行して出力する(indentしない場合は改行のみ)
+    pub fn diff_anchors(mut self, enabled: bool) -> Self {
+        self.diff_anchors = enabled;
+        self
+    }
+
+    /// 数値として正しい文字列(`"8080"`)を、quoteせずに数値(`8080`)として出力する
+    /// **非可逆な変換**で、出力を読み込んでも元の`Node::StringValue`には戻らない
+    /// 文字列と数値を区別できない出力先向けにのみ使う
+    pub fn unquote_numeric_strings(mut self, enabled: bool) -> Self {
+        self.unquote_numeric_strings = enabled;
+        self
+    }
+
+    /// `pointer`の位置にあるobjectのarrayを、各要素の`key`の値で並び替えて出力する
+    /// 並び替えは安定で、`key`を持たない要素は後ろに置く
+    /// 順番に意味のあるarrayを崩さないよう、指定したpathにのみ適用する
+    pub fn sort_array_by(mut self, pointer: &str, key: &str) -> Self {
+        self.array_sort_keys
+            .push((pointer.to_string(), key.to_string()));
+        self
+    }
+
+    /// object/arrayの要素ごとに改行し、`width`個の空白でindentする
+    pub fn indent(mut self, width: usize) -> Self {
+        self.indent = Some(width);
+        self
+    }
+
+    /// 文字列(keyを含む)の`/`を`\/`として出力する
+    /// HTMLに埋め込む場合に`</script>`を避けるために使う
+    pub fn escape_forward_slashes(mut self, enabled: bool) -> Self {
+        self.escape_forward_slashes = enabled;
+        self
+    }
+
+    /// 文字列(keyを含む)の`\uXXXX`を実際の文字として出力する
+    /// 制御文字や対になっていないsurrogateなど、escapeが必要なものはそのまま出力する
+    pub fn decode_unicode_escapes(mut self, enabled: bool) -> Self {
+        self.decode_unicode_escapes = enabled;
+        self
+    }
+
+    /// 数値を正規化した表記(`1.00`は`1`、`1E2`は`100`)で出力する
+    /// hashや比較のための出力向けで、正規化できない数値は元の表記のまま出力する
+    pub fn canonical_numbers(mut self, enabled: bool) -> Self {
+        self.canonical_numbers = enabled;
+        self
+    }
+
+    /// `:`と`,`の後ろに空白を入れる
+    pub fn spaced(mut self, enabled: bool) -> Self {
+        self.spaced = enabled;
+        self
+    }
+}
+
+pub struct Serializer<'a> {
+    options: &'a SerializeOptions,
+    path: JsonPath,
+    comments: Option<&'a Comments>,
+}
+
+impl<'a> Serializer<'a> {
+    pub fn new(options: &'a SerializeOptions) -> Self {
+        Serializer {
+            options,
+            path: JsonPath::new(),
+            comments: None,
+        }
+    }
+
+    /// `comments`をJSONCのコメントとして出力する
+    pub fn with_comments(options: &'a SerializeOptions, comments: &'a Comments) -> Self {
+        Serializer {
+            comments: Some(comments),
+            ..Self::new(options)
+        }
+    }
+
+    pub fn serialize(self, node: &Node) -> String {
+        let mut output = String::new();
+        self.write(node, &mut output)
+            .expect("Stringへの書き込みは失敗しません。");
+        output
+    }
+
+    /// 部分木ごとにStringを作らず、`out`に直接書き込む
+    pub fn write(mut self, node: &Node, out: &mut dyn Write) -> fmt::Result {
+        self.write_leading_comments(out)?;
+        self.write_node(node, out)?;
+        if let Some(comments) = self.comments {
+            for comment in comments.end() {
+                self.write_break(0, out)?;
+                self.write_comment(comment, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_node(&mut self, node: &Node, out: &mut dyn Write) -> fmt::Result {
+        if let Some(formatter) = self.options.formatter {
+            if let Some(raw) = formatter(&self.path, node) {
+                return out.write_str(&raw);
+            }
+        }
+        match node {
+            Node::StringValue(value)
+                if self.options.unquote_numeric_strings && is_json_number(value) =>
+            {
+                out.write_str(value)
+            }
+            Node::StringValue(value) => self.write_string(value, out),
+            Node::Number(value) if self.options.canonical_numbers => {
+                match canonical_number(value) {
+                    Some(canonical) => out.write_str(&canonical),
+                    None => out.write_str(value),
+                }
+            }
+            Node::Number(value) => out.write_str(value),
+            Node::Boolean(value) => out.write_str(if *value { "true" } else { "false" }),
+            Node::Null => out.write_str("null"),
+            Node::Array(items) => {
+                let items = self.sorted_items(items);
+                let level = self.path.segments().len();
+                out.write_char('[')?;
+                for (index, (original, item)) in items.iter().enumerate() {
+                    if index > 0 {
+                        self.write_separator(',', out)?;
+                    }
+                    self.write_break(level + 1, out)?;
+                    // コメントとformatterは並び替える前の位置に対応する
+                    self.path.push(PathSegment::Index(*original));
+                    self.write_leading_comments(out)?;
+                    self.write_node(item, out)?;
+                    self.path.pop();
+                }
+                let has_trailing = self.write_trailing_comments(out)?;
+                if !items.is_empty() || has_trailing {
+                    self.write_break(level, out)?;
+                }
+                out.write_char(']')
+            }
+            Node::Object(members)
+                if self.options.diff_anchors && self.path.segments().is_empty() =>
+            {
+                self.write_anchored_object(members, out)
+            }
+            Node::Object(members) => {
+                let level = self.path.segments().len();
+                out.write_char('{')?;
+                for (index, (key, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        self.write_separator(',', out)?;
+                    }
+                    self.write_break(level + 1, out)?;
+                    self.path.push(PathSegment::Key(key.clone()));
+                    self.write_leading_comments(out)?;
+                    self.write_string(key, out)?;
+                    self.write_separator(':', out)?;
+                    self.write_node(value, out)?;
+                    self.path.pop();
+                }
+                let has_trailing = self.write_trailing_comments(out)?;
+                if !members.is_empty() || has_trailing {
+                    self.write_break(level, out)?;
+                }
+                out.write_char('}')
+            }
+        }
+    }
+
+    fn write_anchored_object(
+        &mut self,
+        members: &BTreeMap<String, Node>,
+        out: &mut dyn Write,
+    ) -> fmt::Result {
+        out.write_char('{')?;
+        for (index, (key, value)) in members.iter().enumerate() {
+            self.write_break(1, out)?;
+            self.path.push(PathSegment::Key(key.clone()));
+            self.write_leading_comments(out)?;
+            self.write_string(key, out)?;
+            self.write_separator(':', out)?;
+            self.write_node(value, out)?;
+            if index + 1 < members.len() {
+                out.write_char(',')?;
+            }
+            let anchor = Comment::Line(format!(" #{}", anchor_hash(&self.path)));
+            out.write_char(' ')?;
+            self.write_comment(&anchor, out)?;
+            self.path.pop();
+        }
+        let has_trailing = self.write_trailing_comments(out)?;
+        if !members.is_empty() || has_trailing {
+            self.write_break(0, out)?;
+        }
+        out.write_char('}')
+    }
+
+    /// 並び替えた要素と、並び替える前のindex
+    fn sorted_items<'b>(&self, items: &'b [Node]) -> Vec<(usize, &'b Node)> {
+        let mut items: Vec<(usize, &Node)> = items.iter().enumerate().collect();
+        if self.options.array_sort_keys.is_empty() {
+            return items;
+        }
+        let pointer = self.path.to_pointer();
+        if let Some((_, key)) = self
+            .options
+            .array_sort_keys
+            .iter()
+            .find(|(path, _)| *path == pointer)
+        {
+            items.sort_by(|(_, a), (_, b)| compare_by_key(a, b, key));
+        }
+        items
+    }
+
+    /// 現在のpathのnodeの直前のコメントを出力する
+    fn write_leading_comments(&mut self, out: &mut dyn Write) -> fmt::Result {
+        let Some(comments) = self.comments.filter(|comments| !comments.is_empty()) else {
+            return Ok(());
+        };
+        let level = self.path.segments().len();
+        for comment in comments.leading(&self.path.to_pointer()) {
+            self.write_comment(comment, out)?;
+            self.write_break(level, out)?;
+        }
+        Ok(())
+    }
+
+    /// 現在のpathのobject/arrayの閉じ括弧の直前のコメントを出力する
+    /// 1つ以上出力した場合は`true`
+    fn write_trailing_comments(&mut self, out: &mut dyn Write) -> Result<bool, fmt::Error> {
+        let Some(comments) = self.comments.filter(|comments| !comments.is_empty()) else {
+            return Ok(false);
+        };
+        let level = self.path.segments().len();
+        let trailing = comments.trailing(&self.path.to_pointer());
+        for comment in trailing {
+            self.write_break(level + 1, out)?;
+            self.write_comment(comment, out)?;
+        }
+        Ok(!trailing.is_empty())
+    }
+
+    /// indentしない場合、行コメントの後ろには改行だけを出力する
+    fn write_comment(&self, comment: &Comment, out: &mut dyn Write) -> fmt::Result {
+        match comment {
+            Comment::Line(text) => write!(out, "//{}", text)?,
+            Comment::Block(text) => write!(out, "/*{}*/", text)?,
+        }
+        if self.options.indent.is_none() && matches!(comment, Comment::Line(_)) {
+            out.write_char('\n')?;
+        }
+        Ok(())
+    }
+
+    /// indentする場合、改行して`level`段indentする
+    fn write_break(&self, level: usize, out: &mut dyn Write) -> fmt::Result {
+        match self.options.indent {
+            Some(width) => write!(out, "\n{:1$}", "", width * level),
+            None => Ok(()),
+        }
+    }
+
+    /// 文字列はescapeされた状態で保持しているので、そのまま`"`で囲む
+    fn write_string(&self, value: &str, out: &mut dyn Write) -> fmt::Result {
+        let decoded;
+        let value = if self.options.decode_unicode_escapes {
+            decoded = decode_unicode_escapes(value);
+            decoded.as_str()
+        } else {
+            value
+        };
+        if !self.options.escape_forward_slashes {
+            return write!(out, "\"{}\"", value);
+        }
+        out.write_char('"')?;
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                // escape済みの文字はそのまま出力する
+                '\\' => {
+                    out.write_char(c)?;
+                    if let Some(escaped) = chars.next() {
+                        out.write_char(escaped)?;
+                    }
+                }
+                '/' => out.write_str("\\/")?,
+                c => out.write_char(c)?,
+            }
+        }
+        out.write_char('"')
+    }
+
+    fn write_separator(&self, separator: char, out: &mut dyn Write) -> fmt::Result {
+        out.write_char(separator)?;
+        // indentする場合、`,`の後ろは改行になる
+        if self.options.spaced || (self.options.indent.is_some() && separator == ':') {
+            out.write_char(' ')?;
+        }
+        Ok(())
+    }
+}
+
+/// objectの`key`の値で比較する、`key`を持たない要素は後ろ
+fn compare_by_key(a: &Node, b: &Node, key: &str) -> Ordering {
+    let a = a.as_object().and_then(|members| members.get(key));
+    let b = b.as_object().and_then(|members| members.get(key));
+    match (a, b) {
+        (Some(a), Some(b)) => compare_values(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// 数値は数値として比較し、型が異なる場合は型の順番で比較する
+fn compare_values(a: &Node, b: &Node) -> Ordering {
+    fn rank(node: &Node) -> u8 {
+        match node {
+            Node::Null => 0,
+            Node::Boolean(_) => 1,
+            Node::Number(_) => 2,
+            Node::StringValue(_) => 3,
+            Node::Array(_) => 4,
+            Node::Object(_) => 5,
+        }
+    }
+
+    match (a, b) {
+        (Node::Number(_), Node::Number(_)) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Node::StringValue(a), Node::StringValue(b)) => a.cmp(b),
+        (Node::Boolean(a), Node::Boolean(b)) => a.cmp(b),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+/// key pathのJSON PointerからFNV-1a(32bit)で求めたhash
+fn anchor_hash(path: &JsonPath) -> String {
+    let hash = path.to_pointer().bytes().fold(0x811c9dc5u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    });
+    format!("{:08x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    #[test]
+    fn formatter_should_replace_value() {
+        fn mask_password(path: &JsonPath, node: &Node) -> Option<String> {
+            match (path.last_key(), node) {
+                (Some("password"), Node::StringValue(_)) => Some(r#""***""#.to_string()),
+                _ => None,
+            }
+        }
+
+        let node = Node::Object(BTreeMap::from([
+            ("name".to_string(), Node::StringValue("sato".to_string())),
+            (
+                "password".to_string(),
+                Node::StringValue("secret".to_string()),
+            ),
+            (
+                "db".to_string(),
+                Node::Object(BTreeMap::from([
+                    (
+                        "password".to_string(),
+                        Node::StringValue("root".to_string()),
+                    ),
+                    ("port".to_string(), Node::Number("5432".to_string())),
+                ])),
+            ),
+        ]));
+        let options = SerializeOptions::new().formatter(mask_password);
+        assert_eq!(
+            r#"{"db":{"password":"***","port":5432},"name":"sato","password":"***"}"#,
+            node.to_json_string_with(&options)
+        );
+        assert_eq!(
+            r#"{"db":{"password":"root","port":5432},"name":"sato","password":"secret"}"#,
+            node.to_json_string()
+        );
+    }
+
+    #[test]
+    fn formatter_should_receive_path() {
+        fn quote_numbers_in_ids(path: &JsonPath, node: &Node) -> Option<String> {
+            match (path.to_pointer().starts_with("/ids/"), node) {
+                (true, Node::Number(value)) => Some(format!(r#""{}""#, value)),
+                _ => None,
+            }
+        }
+
+        let node = Node::Object(BTreeMap::from([
+            (
+                "ids".to_string(),
+                Node::Array(vec![
+                    Node::Number("1".to_string()),
+                    Node::Number("2".to_string()),
+                ]),
+            ),
+            ("count".to_string(), Node::Number("2".to_string())),
+        ]));
+        let options = SerializeOptions::new().formatter(quote_numbers_in_ids);
+        assert_eq!(
+            r#"{"count":2,"ids":["1","2"]}"#,
+            node.to_json_string_with(&options)
+        );
+    }
+
+    #[test]
+    fn diff_anchors_should_be_deterministic() {
+        let node = Node::Object(BTreeMap::from([
+            ("name".to_string(), Node::StringValue("x".to_string())),
+            (
+                "db".to_string(),
+                Node::Object(BTreeMap::from([(
+                    "port".to_string(),
+                    Node::Number("5432".to_string()),
+                )])),
+            ),
+        ]));
+        let options = SerializeOptions::new().diff_anchors(true);
+        let output = node.to_json_string_with(&options);
+        assert_eq!(
+            "{\"db\":{\"port\":5432}, // #e7af7544\n\"name\":\"x\" // #7be2c3bd\n}",
+            output
+        );
+        assert_eq!(output, node.to_json_string_with(&options));
+
+        // indentは全ての段で指定した幅になる
+        let pretty = SerializeOptions::pretty().indent(4).diff_anchors(true);
+        assert_eq!(
+            r#"{
+    "db": {
+        "port": 5432
+    }, // #e7af7544
+    "name": "x" // #7be2c3bd
+}"#,
+            node.to_json_string_with(&pretty)
+        );
+
+        // 出力はJSONCとして読み込める
+        for output in [output, node.to_json_string_with(&pretty)] {
+            let tokens = Lexer::new(&output).tokenize().unwrap();
+            assert_eq!(node, Parser::new(tokens).parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn compact_spaced_should_be_single_line() {
+        let node = Node::Object(BTreeMap::from([
+            ("a".to_string(), Node::Number("1".to_string())),
+            (
+                "b".to_string(),
+                Node::Array(vec![
+                    Node::Number("2".to_string()),
+                    Node::Object(BTreeMap::from([
+                        ("c".to_string(), Node::Null),
+                        ("d".to_string(), Node::StringValue("x".to_string())),
+                    ])),
+                ]),
+            ),
+        ]));
+        assert_eq!(
+            r#"{"a": 1, "b": [2, {"c": null, "d": "x"}]}"#,
+            node.to_json_string_with(&SerializeOptions::compact_spaced())
+        );
+        assert_eq!(
+            r#"{"a":1,"b":[2,{"c":null,"d":"x"}]}"#,
+            node.to_json_string()
+        );
+    }
+
+    #[test]
+    fn unquote_numeric_strings_should_emit_numbers() {
+        let node = Node::Object(BTreeMap::from([
+            ("port".to_string(), Node::StringValue("8080".to_string())),
+            (
+                "host".to_string(),
+                Node::StringValue("localhost".to_string()),
+            ),
+            (
+                "version".to_string(),
+                Node::StringValue("1.2.3".to_string()),
+            ),
+        ]));
+        let options = SerializeOptions::new().unquote_numeric_strings(true);
+        assert_eq!(
+            r#"{"host":"localhost","port":8080,"version":"1.2.3"}"#,
+            node.to_json_string_with(&options)
+        );
+        assert_eq!(
+            r#"{"host":"localhost","port":"8080","version":"1.2.3"}"#,
+            node.to_json_string()
+        );
+    }
+
+    #[test]
+    fn sort_array_by_should_sort_objects_at_path() {
+        fn user(id: &str, name: &str) -> Node {
+            Node::Object(BTreeMap::from([
+                ("id".to_string(), Node::Number(id.to_string())),
+                ("name".to_string(), Node::StringValue(name.to_string())),
+            ]))
+        }
+
+        let users = Node::Array(vec![
+            user("10", "a"),
+            Node::Object(BTreeMap::from([(
+                "name".to_string(),
+                Node::StringValue("no id".to_string()),
+            )])),
+            user("2", "b"),
+            user("10", "c"),
+            user("2", "d"),
+        ]);
+        let node = Node::Object(BTreeMap::from([
+            ("users".to_string(), users.clone()),
+            ("others".to_string(), users),
+        ]));
+        let options = SerializeOptions::new().sort_array_by("/users", "id");
+        let output = node.to_json_string_with(&options);
+        // 同じidの要素は元の順番を保ち、idのない要素は後ろ
+        assert_eq!(
+            concat!(
+                r#"{"others":[{"id":10,"name":"a"},{"name":"no id"},{"id":2,"name":"b"},{"id":10,"name":"c"},{"id":2,"name":"d"}],"#,
+                r#""users":[{"id":2,"name":"b"},{"id":2,"name":"d"},{"id":10,"name":"a"},{"id":10,"name":"c"},{"name":"no id"}]}"#
+            ),
+            output
+        );
+    }
+
+    #[test]
+    fn sort_array_by_should_keep_comments_on_original_items() {
+        let input = r#"{"users": [
+  // second
+  {"id": 2},
+  // first
+  {"id": 1}
+]}"#;
+        let tokens = Lexer::new(input).tokenize().unwrap();
+        let (node, comments) = Parser::new(tokens).parse_with_comments().unwrap();
+        let options = SerializeOptions::pretty().sort_array_by("/users", "id");
+        assert_eq!(
+            r#"{
+  "users": [
+    // first
+    {
+      "id": 1
+    },
+    // second
+    {
+      "id": 2
+    }
+  ]
+}"#,
+            Serializer::with_comments(&options, &comments).serialize(&node)
+        );
+    }
+
+    #[test]
+    fn escape_forward_slashes_should_escape_bare_slashes() {
+        let node = Node::object([("a/b", Node::StringValue(r#"a/b\/c\\/"#.to_string()))]);
+        assert_eq!(r#"{"a/b":"a/b\/c\\/"}"#, node.to_json_string());
+        let options = SerializeOptions::new().escape_forward_slashes(true);
+        assert_eq!(
+            r#"{"a\/b":"a\/b\/c\\\/"}"#,
+            node.to_json_string_with(&options)
+        );
+    }
+
+    #[test]
+    fn decode_unicode_escapes_should_emit_characters_only_when_enabled() {
+        let node = Node::Object(BTreeMap::from([(
+            r"\u3042".to_string(),
+            Node::StringValue(r"\uD83D\uDE00".to_string()),
+        )]));
+        assert_eq!(r#"{"\u3042":"\uD83D\uDE00"}"#, node.to_json_string());
+        let options = SerializeOptions::new().decode_unicode_escapes(true);
+        assert_eq!(r#"{"あ":"😀"}"#, node.to_json_string_with(&options));
+    }
+
+    #[test]
+    fn canonical_numbers_should_normalize_only_when_enabled() {
+        let node = Node::array(["1.00", "1E2", "NaN"].map(|n| Node::Number(n.to_string())));
+        assert_eq!("[1.00,1E2,NaN]", node.to_json_string());
+        let options = SerializeOptions::new().canonical_numbers(true);
+        assert_eq!("[1,100,NaN]", node.to_json_string_with(&options));
+    }
+
+    #[test]
+    fn indent_should_emit_multiline_output() {
+        let node = Node::Object(BTreeMap::from([
+            ("a".to_string(), Node::Number("1".to_string())),
+            (
+                "b".to_string(),
+                Node::Array(vec![
+                    Node::Number("2".to_string()),
+                    Node::Object(BTreeMap::from([("c".to_string(), Node::Null)])),
+                ]),
+            ),
+            ("d".to_string(), Node::Array(vec![])),
+            ("e".to_string(), Node::Object(BTreeMap::new())),
+        ]));
+        assert_eq!(
+            r#"{
+  "a": 1,
+  "b": [
+    2,
+    {
+      "c": null
+    }
+  ],
+  "d": [],
+  "e": {}
+}"#,
+            node.to_json_string_with(&SerializeOptions::pretty())
+        );
+        assert_eq!(
+            "[\n    1\n]",
+            Node::Array(vec![Node::Number("1".to_string())])
+                .to_json_string_with(&SerializeOptions::new().indent(4))
+        );
+    }
+
+    #[test]
+    fn indent_should_place_comments_on_own_lines() {
+        let input = "// top\n{/* a */\"a\": [1, // two\n 2 // end\n]}";
+        let tokens = Lexer::new(input).tokenize().unwrap();
+        let (node, comments) = Parser::new(tokens).parse_with_comments().unwrap();
+        let options = SerializeOptions::pretty();
+        let output = Serializer::with_comments(&options, &comments).serialize(&node);
+        assert_eq!(
+            r#"// top
+{
+  /* a */
+  "a": [
+    1,
+    // two
+    2
+    // end
+  ]
+}"#,
+            output
+        );
+    }
+}
@@ -1,13 +1,54 @@
+use std::fmt;
 use thiserror::Error;
 
 /// Location情報
 /// (start, end)で保持する
 /// ```
+/// # use jsonc_wasm::Location;
+/// let (start, end) = (0, 3);
 /// let a = Location(start, end);
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Location(pub usize, pub usize);
 
+/// 1始まりの行と列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Position { line: 1, column: 1 }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// 値と、その入力上の位置
+/// `position`は`location`の開始位置の行と列
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub location: Location,
+    pub position: Position,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(value: T, location: Location, position: Position) -> Self {
+        Spanned {
+            value,
+            location,
+            position,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Token {
     OpenBrace,    // `{`
@@ -18,11 +59,13 @@ pub enum Token {
     Number(String), // 浮動少数誤差を扱わないため、String
     Boolean(bool),
     Null,
+    Identifier(String), // JSON5のquoteされていないkey
     CommentLine(String),
     CommentBlock(String),
     Comma,
     Colon,
     WhiteSpaces(i32), // Length
+    Tabs(i32),        // Length
     BreakLine,
 }
 
@@ -34,4 +77,38 @@ pub enum LexerError {
     NotExistTerminalSymbol, // 終端記号が不在
     #[error("Not escape string")]
     NotEscapeString,
+    #[error("Invalid unicode escape")]
+    InvalidUnicodeEscape(Location),
+    #[error("Unexpected char `{0}`")]
+    UnexpectedChar(char, Location),
+    #[error("Invalid number `{0}`")]
+    InvalidNumber(String, Location), // 数字を含まない`-`など
+    #[error("Input exceeds max length `{0}` bytes")]
+    InputTooLarge(usize),
+    #[error("String exceeds max length `{0}` chars")]
+    StringTooLong(usize, Location), // 上限と、文字列の開始のquoteから上限を超えた位置まで
+    #[error("Unterminated string")]
+    UnterminatedString(Location), // 開始のquoteの位置
+    #[error("Unterminated block comment")]
+    UnterminatedComment(Location), // 開始の`/*`の位置
+    #[error("Unescaped control char {0:?} in string")]
+    UnescapedControlChar(char, Location),
+}
+
+impl LexerError {
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            LexerError::InvalidChars(_, location)
+            | LexerError::InvalidUnicodeEscape(location)
+            | LexerError::UnexpectedChar(_, location)
+            | LexerError::UnescapedControlChar(_, location)
+            | LexerError::InvalidNumber(_, location)
+            | LexerError::UnterminatedString(location)
+            | LexerError::UnterminatedComment(location)
+            | LexerError::StringTooLong(_, location) => Some(location),
+            LexerError::NotExistTerminalSymbol
+            | LexerError::NotEscapeString
+            | LexerError::InputTooLarge(_) => None,
+        }
+    }
 }
@@ -1,3 +1,222 @@
 pub fn is_number_token_char(c: char) -> bool {
     c.is_numeric() | matches!(c, '.' | '-' | 'e' | 'E')
 }
+
+pub fn is_identifier_start_char(c: char) -> bool {
+    c.is_ascii_alphabetic() | matches!(c, '_' | '$')
+}
+
+pub fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() | matches!(c, '_' | '$')
+}
+
+/// JSON5の`Infinity`/`-Infinity`/`NaN`
+pub fn is_non_finite_number(value: &str) -> bool {
+    matches!(value, "Infinity" | "-Infinity" | "NaN")
+}
+
+/// 16進数の数字列を10進数の文字列に変換する
+/// 桁数に上限はなく、16進数以外の文字を含む場合は`None`
+pub fn hex_to_decimal(hex: &str) -> Option<String> {
+    let mut digits = vec![0u32]; // 10進数の各桁、下の桁から
+    for c in hex.chars() {
+        let mut carry = c.to_digit(16)?;
+        for digit in digits.iter_mut() {
+            let value = *digit * 16 + carry;
+            *digit = value % 10;
+            carry = value / 10;
+        }
+        while carry > 0 {
+            digits.push(carry % 10);
+            carry /= 10;
+        }
+    }
+    digits
+        .iter()
+        .rev()
+        .map(|digit| char::from_digit(*digit, 10))
+        .collect()
+}
+
+/// JSON5の`.5`/`5.`/`+5`をJSONの数値に正規化する
+/// 正規化してもJSONの数値にならない場合は`None`
+pub fn normalize_json5_number(value: &str) -> Option<String> {
+    let (sign, unsigned) = match (value.strip_prefix('+'), value.strip_prefix('-')) {
+        (Some(unsigned), _) => ("", unsigned),
+        (_, Some(unsigned)) => ("-", unsigned),
+        _ => ("", value),
+    };
+    if unsigned.starts_with(['+', '-']) {
+        return None;
+    }
+    let (mantissa, exponent) =
+        unsigned.split_at(unsigned.find(['e', 'E']).unwrap_or(unsigned.len()));
+    let mantissa = mantissa.strip_suffix('.').unwrap_or(mantissa);
+    let zero = if mantissa.starts_with('.') { "0" } else { "" };
+    let number = format!("{}{}{}{}", sign, zero, mantissa, exponent);
+    is_json_number(&number).then_some(number)
+}
+
+/// 数値の表記を正規化する(`1.00`は`1`、`1E2`は`100`、`0.0000001`は`1e-7`)
+/// 10進数のまま変換するため精度は落ちない
+/// 整数部と小数点の位置はECMAScriptの`Number.prototype.toString`と同じ規則で決める
+/// JSONの数値でない場合や、指数が大きすぎる場合は`None`
+pub fn canonical_number(value: &str) -> Option<String> {
+    if !is_json_number(value) {
+        return None;
+    }
+    let (negative, unsigned) = match value.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, value),
+    };
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(index) => (
+            &unsigned[..index],
+            unsigned[index + 1..].parse::<i64>().ok()?,
+        ),
+        None => (unsigned, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", integer, fraction);
+    let digits = digits.trim_start_matches('0');
+    let significant = digits.trim_end_matches('0');
+    if significant.is_empty() {
+        return Some("0".to_string());
+    }
+    // 値は`significant * 10^exponent`
+    let exponent = exponent
+        .checked_sub(fraction.len() as i64)?
+        .checked_add((digits.len() - significant.len()) as i64)?;
+    let point = (significant.len() as i64).checked_add(exponent)?; // 先頭から小数点までの桁数
+    let body = if exponent >= 0 && point <= 21 {
+        format!("{}{}", significant, "0".repeat(exponent as usize))
+    } else if exponent < 0 && point > 0 {
+        let (integer, fraction) = significant.split_at(point as usize);
+        format!("{}.{}", integer, fraction)
+    } else if point <= 0 && point > -6 {
+        format!("0.{}{}", "0".repeat(-point as usize), significant)
+    } else {
+        let (first, rest) = significant.split_at(1);
+        let fraction = if rest.is_empty() {
+            String::new()
+        } else {
+            format!(".{}", rest)
+        };
+        format!("{}{}e{}", first, fraction, point - 1)
+    };
+    Some(if negative { format!("-{}", body) } else { body })
+}
+
+/// RFC 8259の数値の文法に沿っているか
+/// `-? (0 | [1-9][0-9]*) (.[0-9]+)? ([eE][+-]?[0-9]+)?`
+pub fn is_json_number(value: &str) -> bool {
+    fn skip_digits(bytes: &[u8], mut index: usize) -> usize {
+        while bytes.get(index).is_some_and(u8::is_ascii_digit) {
+            index += 1;
+        }
+        index
+    }
+
+    let bytes = value.as_bytes();
+    let mut index = usize::from(bytes.first() == Some(&b'-'));
+    match bytes.get(index) {
+        Some(b'0') => index += 1,
+        Some(b'1'..=b'9') => index = skip_digits(bytes, index),
+        _ => return false,
+    }
+    if bytes.get(index) == Some(&b'.') {
+        let start = index + 1;
+        index = skip_digits(bytes, start);
+        if index == start {
+            return false;
+        }
+    }
+    if matches!(bytes.get(index), Some(b'e' | b'E')) {
+        index += 1;
+        if matches!(bytes.get(index), Some(b'+' | b'-')) {
+            index += 1;
+        }
+        let start = index;
+        index = skip_digits(bytes, start);
+        if index == start {
+            return false;
+        }
+    }
+    index == bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_to_decimal_should_convert_without_overflow() {
+        assert_eq!(Some("0".to_string()), hex_to_decimal("0"));
+        assert_eq!(Some("255".to_string()), hex_to_decimal("0FF"));
+        assert_eq!(
+            Some("340282366920938463463374607431768211456".to_string()),
+            hex_to_decimal("100000000000000000000000000000000")
+        );
+        assert_eq!(None, hex_to_decimal("FG"));
+    }
+
+    #[test]
+    fn normalize_json5_number_should_return_json_number() {
+        for (value, expected) in [
+            (".5", "0.5"),
+            ("5.", "5"),
+            ("+5", "5"),
+            ("-.5", "-0.5"),
+            ("+.5e2", "0.5e2"),
+            ("5.E3", "5E3"),
+            ("1.5", "1.5"),
+        ] {
+            assert_eq!(Some(expected.to_string()), normalize_json5_number(value));
+        }
+        for value in [".", "+", "+-5", "5..", ".e5", "01"] {
+            assert_eq!(
+                None,
+                normalize_json5_number(value),
+                "`{}`は数値ではありません。",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn canonical_number_should_normalize_notation() {
+        for (value, expected) in [
+            ("1.00", "1"),
+            ("1E2", "100"),
+            ("100", "100"),
+            ("0.50", "0.5"),
+            ("-0.0", "0"),
+            ("12.340e1", "123.4"),
+            ("0.000001", "0.000001"),
+            ("0.0000001", "1e-7"),
+            ("1e21", "1e21"),
+            ("123e19", "1.23e21"),
+            ("-2.5E-10", "-2.5e-10"),
+            ("12345678901234567890123", "1.2345678901234567890123e22"),
+        ] {
+            assert_eq!(
+                Some(expected.to_string()),
+                canonical_number(value),
+                "`{}`",
+                value
+            );
+        }
+        assert_eq!(None, canonical_number("NaN"));
+        assert_eq!(None, canonical_number("1e99999999999999999999"));
+    }
+
+    #[test]
+    fn is_json_number_should_follow_rfc8259() {
+        for value in ["0", "-0", "8080", "-1.5", "1e10", "1E+2", "0.5e-3"] {
+            assert!(is_json_number(value), "`{}`は数値です。", value);
+        }
+        for value in ["", "-", "01", "1.", ".5", "+1", "1e", "1.2.3", "0x10", " 1"] {
+            assert!(!is_json_number(value), "`{}`は数値ではありません。", value);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::process::Command;
+
+const DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/cli");
+
+fn run(args: &[&str]) -> (Option<i32>, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_jsonc"))
+        .args(args)
+        .output()
+        .expect("binaryを実行できます。");
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn cli_should_convert_file() {
+    let file = format!("{}/sample.jsonc", DATA_DIR);
+    let (code, stdout, _) = run(&["--strip-comments", &file]);
+    assert_eq!(Some(0), code);
+    assert_eq!("{\"hosts\":[\"a\",\"b\"],\"port\":8080}\n", stdout);
+
+    let (code, stdout, _) = run(&["--indent", "4", &file]);
+    assert_eq!(Some(0), code);
+    assert_eq!(
+        r#"{
+    "hosts": [
+        "a",
+        "b"
+    ],
+    // server settings
+    "port": 8080
+}
+"#,
+        stdout
+    );
+}
+
+#[test]
+fn cli_should_fail_with_invalid_input() {
+    let file = format!("{}/invalid.jsonc", DATA_DIR);
+    let (code, stdout, stderr) = run(&[&file]);
+    assert_eq!(Some(1), code);
+    assert!(stdout.is_empty());
+    assert_eq!(
+        format!(
+            r#"error: Unexpected `:` where a value was expected at line 3, column 11
+ --> {}:3:11
+  |
+3 |   "hosts":: []
+  |           ^
+"#,
+            file
+        ),
+        stderr
+    );
+
+    let (code, _, stderr) = run(&["--indent"]);
+    assert_eq!(Some(2), code);
+    assert!(stderr.starts_with("error: `--indent` requires a number"));
+}